@@ -3,6 +3,7 @@ use std::time::Instant;
 
 fn main() {
   performance_check_triangulate();
+  performance_check_large_cycle();
 }
 
 fn performance_check_triangulate() {
@@ -12,13 +13,38 @@ fn performance_check_triangulate() {
     let a: Vec<Vec<Vec<f64>>> = serde_json::from_str(&data).unwrap();
     let mut a: Vec<f64> = a[0].concat();
 
-    let t = 100;
-    let now = Instant::now();
-    for _i in 0..t {
-      let (_new_data, _indices) = triangulate(&mut a, 2);
+    for backend in [Backend::EarCut, Backend::Monotone] {
+      let t = 100;
+      let now = Instant::now();
+      for _i in 0..t {
+        let (_new_data, _indices) = triangulate_with(&mut a, 2, backend);
+      }
+      let time = now.elapsed().as_millis();
+      let r = format!("{}tries: {}: {:?}: {}(ms)", t, f, backend, time);
+      println!("{}", &r);
     }
-    let time = now.elapsed().as_millis();
-    let r = format!("{}tries: {}: {}(ms)", t, f, time);
-    println!("{}", &r);
   }
-}
\ No newline at end of file
+}
+
+/// A star polygon with n points at random radii around a circle: one large simple cycle.
+fn performance_check_large_cycle() {
+  let mut seed: u64 = 7;
+  let mut random = || {
+    seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    (seed>>11) as f64 / (1u64<<53) as f64
+  };
+  for n in [1000, 10000, 50000] {
+    let mut a: Vec<f64> = Vec::new();
+    for k in 0..n {
+      let t = k as f64/n as f64*std::f64::consts::PI*2.;
+      let r = 100. + random()*20.;
+      a.extend([r*t.cos(), r*t.sin()]);
+    }
+    for backend in [Backend::EarCut, Backend::Monotone] {
+      let now = Instant::now();
+      let (_new_data, _indices) = triangulate_with(&mut a.clone(), 2, backend);
+      let time = now.elapsed().as_millis();
+      println!("star {}: {:?}: {}(ms)", n, backend, time);
+    }
+  }
+}
@@ -0,0 +1,71 @@
+//! Boolean operations between polygons
+//!
+//! Union, intersection, difference and xor of two polygons, each of which may be self-intersecting or have holes.
+//!
+//! Each operand is read the way [`triangulate`] reads it: louvre decomposes it into its simple cycles,
+//! and the region it fills is resolved into simple rings, so that its winding number is 1 inside and 0 outside.
+//! The rings of both operands are decomposed together (with the clip reversed for a difference),
+//! and the operation keeps the cycles by the winding number they bound, e.g. 2 for an intersection.
+//! The boundary of the kept region is returned as simple rings.
+
+use crate::*;
+use crate::fill::{group_polygons, resolve_winding};
+use crate::polygon::{append_mesh, polygon_region, triangulate_resolved};
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BooleanOp {
+  Union,
+  Intersection,
+  /// subject - clip
+  Difference,
+  Xor,
+}
+
+impl BooleanOp {
+  pub fn keeps(&self, in_subject: bool, in_clip: bool) -> bool {
+    match self {
+      BooleanOp::Union => in_subject || in_clip,
+      BooleanOp::Intersection => in_subject && in_clip,
+      BooleanOp::Difference => in_subject && !in_clip,
+      BooleanOp::Xor => in_subject != in_clip,
+    }
+  }
+}
+
+
+/// Boolean operation between two polygon rings, each filled the same as [`triangulate`] fills it.
+///
+/// Returns simple rings (with dim 2): outer rings are CCW and holes are CW.
+pub fn boolean(subject: &[f64], clip: &[f64], dim: usize, op: BooleanOp) -> Vec<Vec<f64>> {
+  boolean_rings(&[subject.to_vec()], &[clip.to_vec()], dim, op)
+}
+
+/// Boolean operation between two polygons with holes: in each, the first ring is the outer ring and the others are holes,
+/// the same as [`triangulate_polygon`] takes them.
+pub fn boolean_rings(subject: &[Vec<f64>], clip: &[Vec<f64>], dim: usize, op: BooleanOp) -> Vec<Vec<f64>> {
+  let subject = polygon_region(subject, dim);
+  let clip = polygon_region(clip, dim);
+  let reverse = op==BooleanOp::Difference;
+  let rings: Vec<Vec<f64>> = subject.into_iter()
+    .chain(clip.into_iter().map(|r| if reverse { r.chunks_exact(2).rev().flatten().copied().collect() } else { r }))
+    .collect();
+  // winding numbers: subject + clip, or subject - clip for a difference
+  resolve_winding(&rings, |w| match op {
+    BooleanOp::Union => w>=1,
+    BooleanOp::Intersection => w>=2,
+    BooleanOp::Difference => w>=1,
+    BooleanOp::Xor => w==1,
+  })
+}
+
+/// Boolean operation between two polygon rings (see [`boolean`]), triangulated.
+/// Returns a new coordinates array and a index array of it, the same as [`triangulate`].
+pub fn triangulate_boolean(subject: &[f64], clip: &[f64], dim: usize, op: BooleanOp) -> (Vec<f64>, Vec<usize>) {
+  let mut new_data: Vec<f64> = Vec::new();
+  let mut indices: Vec<usize> = Vec::new();
+  for polygon in group_polygons(boolean(subject, clip, dim, op)).iter() {
+    append_mesh(triangulate_resolved(polygon), &mut new_data, &mut indices);
+  }
+  (new_data, indices)
+}
@@ -0,0 +1,136 @@
+//! Vertex cache optimization
+//!
+//! [`triangulate`](crate::triangulate) emits triangles in the order of the ear clipping walk, which jumps around the polygon.
+//! [`optimize_vertex_cache`] reorders them for the GPU's post-transform vertex cache with Tipsify
+//! (Sander, Nehab and Barczak, "Fast triangle reordering for vertex locality and reduced overdraw", 2007),
+//! then renumbers the vertices in the order they are first used, for memory locality.
+//! [`acmr`] measures the effect.
+
+
+/// Average cache miss ratio: vertices transformed per triangle, with a FIFO cache of `cache_size` vertices.
+/// It ranges from 3 (no reuse) down to about 0.5 for large regular meshes.
+pub fn acmr(indices: &[usize], cache_size: usize) -> f64 {
+  let triangles = indices.len()/3;
+  if triangles==0 {
+    return 0.;
+  }
+  let mut cache: std::collections::VecDeque<usize> = std::collections::VecDeque::with_capacity(cache_size+1);
+  let mut misses = 0;
+  for &v in indices[..triangles*3].iter() {
+    if !cache.contains(&v) {
+      misses += 1;
+      cache.push_back(v);
+      if cache.len()>cache_size {
+        cache.pop_front();
+      }
+    }
+  }
+  misses as f64 / triangles as f64
+}
+
+/// Reorders the triangles for a vertex cache of `cache_size` vertices (e.g. 16 to 32), and renumbers the vertices
+/// of the coordinates array (with `dim`) in the order of first use. Vertices no triangle uses are dropped.
+///
+/// Returns a new coordinates array and a index array of it. Each triangle keeps its vertices' order, and so its winding.
+pub fn optimize_vertex_cache(new_data: &[f64], dim: usize, indices: &[usize], cache_size: usize) -> (Vec<f64>, Vec<usize>) {
+  let order = tipsify(indices, new_data.len()/dim, cache_size.max(3));
+
+  let mut remap: Vec<usize> = vec![usize::MAX; new_data.len()/dim];
+  let mut data: Vec<f64> = Vec::with_capacity(new_data.len());
+  let mut reordered: Vec<usize> = Vec::with_capacity(order.len()*3);
+  for t in order {
+    for &v in indices[t*3..t*3+3].iter() {
+      if remap[v]==usize::MAX {
+        remap[v] = data.len()/dim;
+        data.extend_from_slice(&new_data[v*dim..v*dim+dim]);
+      }
+      reordered.push(remap[v]);
+    }
+  }
+  (data, reordered)
+}
+
+/// Tipsify: the order of triangles.
+fn tipsify(indices: &[usize], vertices: usize, k: usize) -> Vec<usize> {
+  let triangles = indices.len()/3;
+
+  // ----- vertex -> triangles ----- //
+  // counting sort of the corners by vertex
+  let mut starts: Vec<usize> = vec![0; vertices+1];
+  for &v in indices[..triangles*3].iter() {
+    starts[v+1] += 1;
+  }
+  for v in 1..starts.len() {
+    starts[v] += starts[v-1];
+  }
+  let mut adjacency: Vec<usize> = vec![0; triangles*3];
+  let mut fill = starts.clone();
+  for (c, &v) in indices[..triangles*3].iter().enumerate() {
+    adjacency[fill[v]] = c/3;
+    fill[v] += 1;
+  }
+
+  // live triangles of each vertex, and the time each vertex went into the cache
+  let mut live: Vec<usize> = (0..vertices).map(|v| starts[v+1]-starts[v]).collect();
+  let mut time: Vec<usize> = vec![0; vertices];
+  let mut emitted: Vec<bool> = vec![false; triangles];
+  let mut dead_end: Vec<usize> = Vec::new();
+  let mut order: Vec<usize> = Vec::with_capacity(triangles);
+  let mut stamp = k+1;
+  let mut cursor = 0;
+
+  let mut fanning = if triangles>0 { Some(indices[0]) } else { None };
+  while let Some(f) = fanning {
+    // emit the fan of f
+    let mut candidates: Vec<usize> = Vec::new();
+    for &t in adjacency[starts[f]..starts[f+1]].iter() {
+      if emitted[t] {
+        continue;
+      }
+      for &v in indices[t*3..t*3+3].iter() {
+        dead_end.push(v);
+        candidates.push(v);
+        live[v] -= 1;
+        if stamp-time[v]>k {
+          time[v] = stamp;
+          stamp += 1;
+        }
+      }
+      emitted[t] = true;
+      order.push(t);
+    }
+
+    // next fanning vertex: the candidate staying in the cache the longest after its fan, if it still will be in the cache.
+    let mut best: Option<(usize, usize)> = None;
+    for &v in candidates.iter() {
+      if live[v]==0 {
+        continue;
+      }
+      let priority = if stamp-time[v]+2*live[v]<=k { stamp-time[v] } else { 0 };
+      if best.map_or(true, |(_, p)| priority>p) {
+        best = Some((v, priority));
+      }
+    }
+    fanning = match best {
+      Some((v, _)) => Some(v),
+      None => {
+        // dead end: a recent vertex with live triangles, or else the next one in input order.
+        let mut next: Option<usize> = None;
+        while let Some(d) = dead_end.pop() {
+          if live[d]>0 {
+            next = Some(d);
+            break;
+          }
+        }
+        while next.is_none() && cursor<vertices {
+          if live[cursor]>0 {
+            next = Some(cursor);
+          }
+          cursor += 1;
+        }
+        next
+      },
+    };
+  }
+  order
+}
@@ -0,0 +1,101 @@
+//! Canonical output
+//!
+//! The buffers [`triangulate`] returns depend on where the outline starts and which way it goes around:
+//! the same shape given from another starting point gets its intersections computed over other segments,
+//! its cycles in another order and other ears clipped. That churns snapshot tests and content-addressed caches.
+//!
+//! [`triangulate_canonical`] normalizes the outline first (its direction, and its lowest-leftmost point as the start),
+//! then puts each simple cycle in the same form, orders the cycles by their points, and sorts the triangles.
+//! The arithmetic is plain IEEE 754 f64 with no platform dependent functions,
+//! so equal inputs give byte-identical buffers on native and wasm targets.
+
+use crate::*;
+use crate::polygon::ring_points;
+
+
+/// Triangulates a polygon (see [`triangulate`]) into canonical buffers: polygons which only differ in their starting point
+/// or winding direction (or a repeated closing point, or the sign of zero) give the same `new_data` and `indices`.
+///
+/// - The vertices of each cycle run counter-clockwise from its lowest (then leftmost) point.
+/// - Cycles are ordered by their points, compared as (y, x) pairs.
+/// - Each triangle starts from its smallest index, keeping its CCW winding, and triangles are sorted by their indices.
+pub fn triangulate_canonical(data: &[f64], dim: usize) -> (Vec<f64>, Vec<usize>) {
+  let mut outline = ring_points(data, dim, true);
+  if outline.len()<3 {
+    return (Vec::new(), Vec::new());
+  }
+  // Of both directions, the one whose points compare smaller from the start. A direction by the signed area
+  // could flip for a near-zero area summed from another start, and there is none for a figure 8 whose lobes cancel out.
+  rotate_to_start(&mut outline);
+  let mut reversed = outline.clone();
+  reversed.reverse();
+  rotate_to_start(&mut reversed);
+  if compare(&reversed, &outline)==Ordering::Less {
+    outline = reversed;
+  }
+  let mut outline: Vec<f64> = outline.into_iter().flat_map(|(x, y)| [x, y]).collect();
+
+  let mut rings: Vec<Vec<(f64, f64)>> = resolve_self_intersections(&mut outline, 2).into_iter()
+    .map(|r| {
+      let mut ring: Vec<(f64, f64)> = r.data.chunks_exact(2).map(|p| (p[0], p[1])).collect();
+      rotate_to_start(&mut ring);
+      ring
+    })
+    .collect();
+  rings.sort_by(|a, b| compare(a, b));
+
+  let mut new_data: Vec<f64> = Vec::new();
+  let mut indices: Vec<usize> = Vec::new();
+  for ring in rings.iter() {
+    let offset = new_data.len()/2;
+    let mut ring: Vec<f64> = ring.iter().flat_map(|&(x, y)| [x, y]).collect();
+    let (cycle_data, cycle_indices) = triangulate_simple(&mut ring, 2);
+
+    let mut triangles: Vec<[usize; 3]> = cycle_indices.chunks_exact(3).map(|t| {
+      let k = (0..3).min_by_key(|&k| t[k]).unwrap_or(0);
+      [t[k]+offset, t[(k+1)%3]+offset, t[(k+2)%3]+offset]
+    }).collect();
+    triangles.sort_unstable();
+    new_data.extend(cycle_data);
+    indices.extend(triangles.into_iter().flatten());
+  }
+  (new_data, indices)
+}
+
+/// Rotates a ring to start from its lowest, then leftmost point; among repeated points, the one followed by the smallest points.
+/// Turns -0 into 0.
+pub(crate) fn rotate_to_start(ring: &mut [(f64, f64)]) {
+  for p in ring.iter_mut() {
+    *p = (p.0+0., p.1+0.);
+  }
+  let n = ring.len();
+  let start = {
+    let ring = &*ring;
+    let rotated = |s: usize| (0..n).map(move |k| ring[(s+k)%n]);
+    (0..n).min_by(|&a, &b| compare_iter(rotated(a), rotated(b))).unwrap_or(0)
+  };
+  ring.rotate_left(start);
+}
+
+/// Orders points by y, then x.
+fn compare_point(p: &(f64, f64), q: &(f64, f64)) -> Ordering {
+  p.1.total_cmp(&q.1).then(p.0.total_cmp(&q.0))
+}
+
+fn compare(a: &[(f64, f64)], b: &[(f64, f64)]) -> Ordering {
+  compare_iter(a.iter().copied(), b.iter().copied())
+}
+
+fn compare_iter<I: Iterator<Item = (f64, f64)>>(mut a: I, mut b: I) -> Ordering {
+  loop {
+    match (a.next(), b.next()) {
+      (Some(p), Some(q)) => match compare_point(&p, &q) {
+        Ordering::Equal => continue,
+        o => return o,
+      },
+      (Some(_), None) => return Ordering::Greater,
+      (None, Some(_)) => return Ordering::Less,
+      (None, None) => return Ordering::Equal,
+    }
+  }
+}
@@ -0,0 +1,93 @@
+//! Convex partition
+//!
+//! Many triangles out of [`triangulate`] can be merged back into convex polygons, e.g. for collision shapes.
+//! [`convex_partition`] follows Hertel–Mehlhorn: starting from the triangulation of each simple cycle,
+//! it greedily removes every diagonal whose removal keeps both polygons around it convex.
+//! The result has at most four times as many pieces as the minimum convex partition.
+
+use crate::*;
+use crate::halfedge::HalfEdgeMesh;
+
+
+/// Triangulates a polygon (see [`triangulate`]) and merges the triangles into convex polygons.
+///
+/// Returns counter-clockwise convex polygons (with dim 2), without collinear vertices.
+pub fn convex_partition(data: &mut Vec<f64>, dim: usize) -> Vec<Vec<f64>> {
+  let (new_data, indices) = triangulate(data, dim);
+  convex_partition_mesh(&new_data, &indices)
+}
+
+/// Merges a triangulation (counter-clockwise triangles with dim 2, as louvre makes them) into convex polygons.
+pub fn convex_partition_mesh(new_data: &[f64], indices: &[usize]) -> Vec<Vec<f64>> {
+  let mesh = HalfEdgeMesh::new(new_data, indices);
+  let len = mesh.indices.len();
+  let vertex = |v: usize| (mesh.positions[v*2], mesh.positions[v*2+1]);
+  let point = |h: usize| vertex(mesh.origin(h));
+  // left turn (or straight) at the vertex between half-edges a and b
+  let convex = |a: usize, b: usize| {
+    let ((px, py), (qx, qy), (rx, ry)) = (point(a), point(b), vertex(mesh.target(b)));
+    (qx-px)*(ry-qy) - (qy-py)*(rx-qx) >= 0.
+  };
+
+  // polygons are the cycles of nxt/prv over the half-edges still in use
+  let mut nxt: Vec<usize> = (0..len).map(|h| mesh.next(h)).collect();
+  let mut prv: Vec<usize> = (0..len).map(|h| mesh.prev(h)).collect();
+  let mut removed: Vec<bool> = vec![false; len];
+  // polygon of each triangle, as a union-find
+  let mut parent: Vec<usize> = (0..len/3).collect();
+  fn find(parent: &mut [usize], mut t: usize) -> usize {
+    while parent[t]!=t {
+      parent[t] = parent[parent[t]];
+      t = parent[t];
+    }
+    t
+  }
+
+  // ----- remove inessential diagonals ----- //
+  for h in 0..len {
+    let g = match mesh.twin(h) {
+      Some(g) if g>h => g,
+      _ => continue,
+    };
+    let (p, q) = (find(&mut parent, h/3), find(&mut parent, g/3));
+    if p==q {
+      continue;
+    }
+    // h: a -> b, g: b -> a. Merged, a is entered by prv[h] and left by nxt[g], and b by prv[g] and nxt[h].
+    if !convex(prv[h], nxt[g]) || !convex(prv[g], nxt[h]) {
+      continue;
+    }
+    let (ph, pg, nh, ng) = (prv[h], prv[g], nxt[h], nxt[g]);
+    nxt[ph] = ng;
+    prv[ng] = ph;
+    nxt[pg] = nh;
+    prv[nh] = pg;
+    removed[h] = true;
+    removed[g] = true;
+    parent[q] = p;
+  }
+
+  // ----- collect polygons ----- //
+  let mut visited = removed;
+  let mut polygons: Vec<Vec<f64>> = Vec::new();
+  for start in 0..len {
+    if visited[start] {
+      continue;
+    }
+    let mut polygon: Vec<f64> = Vec::new();
+    let mut h = start;
+    while !visited[h] {
+      visited[h] = true;
+      let (x, y) = point(h);
+      let ((px, py), (nx, ny)) = (point(prv[h]), point(nxt[h]));
+      if (x-px)*(ny-y) - (y-py)*(nx-x) != 0. {
+        polygon.extend([x, y]);
+      }
+      h = nxt[h];
+    }
+    if polygon.len()>=6 {
+      polygons.push(polygon);
+    }
+  }
+  polygons
+}
@@ -0,0 +1,668 @@
+//! Incremental editing
+//!
+//! An editor changes a polygon one vertex at a time; triangulating the whole outline again after every change
+//! doesn't scale to large polygons. [`EditablePolygon`] keeps the outline together with its simple cycles
+//! (see [`resolve_self_intersections`]) and their triangles.
+//! While the outline is simple, an edit only checks its new segments against the segments near them, kept in a grid:
+//! if they meet none, the outline is still simple and only the triangles around the edited point are triangulated again,
+//! found from the point through the neighbours of each triangle.
+//! Otherwise the intersections are found again by the sweep, and only the cycles which changed are triangulated;
+//! the other cycles keep their triangles. Each edit returns a [`Diff`] to update a vertex buffer in place.
+
+use crate::*;
+use crate::polygon::append_mesh;
+use crate::canonical::rotate_to_start;
+use crate::triangulate::{side, ROUNDING};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+
+/// A triangle of an [`EditablePolygon`]. Ids are not reused, so an id names the same triangle until it is removed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Triangle {
+  pub id: usize,
+  /// x, y of its three vertices, counter-clockwise.
+  pub points: [f64; 6],
+}
+
+/// Triangles changed by an edit.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diff {
+  /// Ids of the triangles which are gone, in ascending order.
+  pub removed: Vec<usize>,
+  pub added: Vec<Triangle>,
+}
+
+impl Diff {
+  pub fn is_empty(&self) -> bool {
+    self.removed.is_empty() && self.added.is_empty()
+  }
+}
+
+/// No point or triangle, e.g. across an edge of the cycle itself.
+const NONE: usize = usize::MAX;
+
+/// A simple cycle (CCW) and its triangles, with the id of each triangle.
+///
+/// Points and triangles are kept in slots, which a patch frees and fills again, so that it only touches the slots around an edit:
+/// a free triangle has the id `NONE`, and a free point is in no triangle.
+/// The edges of triangle k are k*3+e, from its point e to the next one.
+struct Cycle {
+  /// See [`cycle_key`]; `None` once the cycle is patched, as no ring resolved before is the same then.
+  key: Option<Vec<u64>>,
+  positions: Vec<f64>,
+  indices: Vec<usize>,
+  ids: Vec<usize>,
+  /// For each edge, the same edge the other way in the triangle across it; `NONE` for the edges of the cycle.
+  twins: Vec<usize>,
+  /// An edge from each point.
+  corners: Vec<usize>,
+  free_points: Vec<usize>,
+  free_triangles: Vec<usize>,
+}
+
+impl Cycle {
+  fn new(key: Vec<u64>, positions: Vec<f64>, indices: Vec<usize>, ids: Vec<usize>) -> Cycle {
+    let mut edges: HashMap<(usize, usize), usize> = HashMap::with_capacity(indices.len());
+    for h in 0..indices.len() {
+      edges.insert((indices[h], indices[next_edge(h)]), h);
+    }
+    let mut twins: Vec<usize> = vec![NONE; indices.len()];
+    let mut corners: Vec<usize> = vec![NONE; positions.len()/2];
+    for (h, twin) in twins.iter_mut().enumerate() {
+      *twin = edges.get(&(indices[next_edge(h)], indices[h])).copied().unwrap_or(NONE);
+      corners[indices[h]] = h;
+    }
+    Cycle { key: Some(key), positions, indices, ids, twins, corners, free_points: Vec::new(), free_triangles: Vec::new() }
+  }
+
+  fn point(&self, v: usize) -> (f64, f64) {
+    (self.positions[v*2], self.positions[v*2+1])
+  }
+
+  fn triangle(&self, k: usize) -> Triangle {
+    let ((ax, ay), (bx, by), (cx, cy)) = (self.point(self.indices[k*3]), self.point(self.indices[k*3+1]), self.point(self.indices[k*3+2]));
+    Triangle { id: self.ids[k], points: [ax, ay, bx, by, cx, cy] }
+  }
+
+  fn triangles(&self) -> impl Iterator<Item = Triangle> + '_ {
+    (0..self.ids.len()).filter(|&k| self.ids[k]!=NONE).map(|k| self.triangle(k))
+  }
+
+  /// The points and triangles in use, in the order of their slots.
+  fn mesh(&self) -> (Vec<f64>, Vec<usize>) {
+    let mut at: Vec<usize> = vec![0; self.positions.len()/2];
+    for &v in self.free_points.iter() {
+      at[v] = NONE;
+    }
+    let mut positions: Vec<f64> = Vec::with_capacity(self.positions.len());
+    for (v, at) in at.iter_mut().enumerate() {
+      if *at!=NONE {
+        *at = positions.len()/2;
+        positions.extend([self.positions[v*2], self.positions[v*2+1]]);
+      }
+    }
+    let indices: Vec<usize> = (0..self.ids.len()).filter(|&k| self.ids[k]!=NONE)
+      .flat_map(|k| self.indices[k*3..k*3+3].iter().map(|&v| at[v]).collect::<Vec<usize>>()).collect();
+    (positions, indices)
+  }
+
+  /// The edges from point v, going round it. All the points are on the cycle, so the triangles at one don't close around it.
+  fn edges_from(&self, v: usize) -> Vec<usize> {
+    let first = self.corners[v];
+    let mut edges: Vec<usize> = vec![first];
+    let mut h = first;
+    // one way round to the cycle, then the other
+    loop {
+      h = self.twins[next_edge(next_edge(h))];
+      if h==NONE || h==first {
+        break;
+      }
+      edges.push(h);
+    }
+    if h==NONE {
+      let mut h = first;
+      while self.twins[h]!=NONE {
+        h = next_edge(self.twins[h]);
+        edges.push(h);
+      }
+    }
+    edges
+  }
+
+  fn add_point(&mut self, (x, y): (f64, f64)) -> usize {
+    match self.free_points.pop() {
+      Some(v) => {
+        self.positions[v*2] = x;
+        self.positions[v*2+1] = y;
+        v
+      },
+      None => {
+        self.positions.extend([x, y]);
+        self.corners.push(NONE);
+        self.corners.len()-1
+      },
+    }
+  }
+
+  fn add_triangle(&mut self) -> usize {
+    self.free_triangles.pop().unwrap_or_else(|| {
+      self.indices.extend([NONE; 3]);
+      self.twins.extend([NONE; 3]);
+      self.ids.push(NONE);
+      self.ids.len()-1
+    })
+  }
+}
+
+/// The next edge of the same triangle.
+fn next_edge(h: usize) -> usize {
+  h - h%3 + (h+1)%3
+}
+
+/// An edit of the outline.
+#[derive(Debug, Clone, Copy)]
+enum Edit {
+  /// The index of the new point.
+  Insert(usize),
+  Move(usize),
+  /// The index the point had.
+  Remove(usize),
+}
+
+/// The points of a simple outline, as slots of its cycle, and a grid of its segments.
+struct Outline {
+  slots: Vec<usize>,
+  grid: SegmentGrid,
+}
+
+/// A polygon outline (with dim 2) which keeps its triangulation up to date through edits.
+pub struct EditablePolygon {
+  data: Vec<f64>,
+  backend: Backend,
+  cycles: Vec<Cycle>,
+  /// The outline, while it is a single simple cycle, so that edits can be patched in.
+  outline: Option<Outline>,
+  next_id: usize,
+}
+
+impl EditablePolygon {
+  /// An editable polygon from a coordinates array with `dim`, like `data` of [`triangulate`].
+  pub fn new(data: &[f64], dim: usize) -> EditablePolygon {
+    EditablePolygon::with_backend(data, dim, Backend::EarCut)
+  }
+
+  /// [`EditablePolygon::new`] with a chosen [`Backend`] for the cycles.
+  pub fn with_backend(data: &[f64], dim: usize, backend: Backend) -> EditablePolygon {
+    let data: Vec<f64> = if dim<2 { Vec::new() } else { data.chunks_exact(dim).flat_map(|p| [p[0], p[1]]).collect() };
+    let mut polygon = EditablePolygon { data, backend, cycles: Vec::new(), outline: None, next_id: 0 };
+    polygon.update();
+    polygon
+  }
+
+  /// The outline, with dim 2.
+  pub fn data(&self) -> &[f64] {
+    &self.data
+  }
+
+  /// Number of points of the outline.
+  pub fn len(&self) -> usize {
+    self.data.len()/2
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.data.is_empty()
+  }
+
+  pub fn point(&self, i: usize) -> (f64, f64) {
+    (self.data[i*2], self.data[i*2+1])
+  }
+
+  /// Number of simple cycles the outline is decomposed into.
+  pub fn cycle_count(&self) -> usize {
+    self.cycles.len()
+  }
+
+  /// The current triangles.
+  pub fn triangles(&self) -> impl Iterator<Item = Triangle> + '_ {
+    self.cycles.iter().flat_map(|c| c.triangles())
+  }
+
+  /// The current triangulation as a coordinates array with dim 2 and a index array of it, like [`triangulate`] returns.
+  pub fn mesh(&self) -> (Vec<f64>, Vec<usize>) {
+    let mut new_data: Vec<f64> = Vec::new();
+    let mut indices: Vec<usize> = Vec::new();
+    for cycle in self.cycles.iter() {
+      append_mesh(cycle.mesh(), &mut new_data, &mut indices);
+    }
+    (new_data, indices)
+  }
+
+  /// Where the outline crosses or touches itself; see [`self_intersections`].
+  pub fn intersections(&self) -> Vec<SelfIntersection> {
+    self_intersections(&mut self.data.clone(), 2)
+  }
+
+  /// Inserts a point before point `i` (after the last one if `i` is the length).
+  ///
+  /// # Panics
+  /// If `i` is greater than the length.
+  pub fn insert(&mut self, i: usize, x: f64, y: f64) -> Diff {
+    assert!(i<=self.len(), "insertion index {} is out of bounds of {} points", i, self.len());
+    self.data.splice(i*2..i*2, [x, y]);
+    self.update_at(Edit::Insert(i))
+  }
+
+  /// Appends a point, as the demo does on every click.
+  pub fn push(&mut self, x: f64, y: f64) -> Diff {
+    self.insert(self.len(), x, y)
+  }
+
+  /// Moves point `i`, e.g. while it is dragged.
+  ///
+  /// # Panics
+  /// If `i` is out of bounds.
+  pub fn move_vertex(&mut self, i: usize, x: f64, y: f64) -> Diff {
+    assert!(i<self.len(), "index {} is out of bounds of {} points", i, self.len());
+    if self.point(i)==(x, y) {
+      return Diff::default();
+    }
+    self.data[i*2] = x;
+    self.data[i*2+1] = y;
+    self.update_at(Edit::Move(i))
+  }
+
+  /// Removes point `i`.
+  ///
+  /// # Panics
+  /// If `i` is out of bounds.
+  pub fn remove(&mut self, i: usize) -> Diff {
+    assert!(i<self.len(), "index {} is out of bounds of {} points", i, self.len());
+    self.data.drain(i*2..i*2+2);
+    self.update_at(Edit::Remove(i))
+  }
+
+  /// Resolves the outline again, keeping the cycles which are still there and triangulating the new ones.
+  fn update(&mut self) -> Diff {
+    let rings = if self.data.len()>=6 { resolve_self_intersections(&mut self.data.clone(), 2) } else { Vec::new() };
+
+    let mut old: HashMap<Vec<u64>, Vec<Cycle>> = HashMap::new();
+    let mut patched: Vec<Cycle> = Vec::new();
+    for mut cycle in self.cycles.drain(..) {
+      match cycle.key.take() {
+        Some(key) => old.entry(key).or_default().push(cycle),
+        None => patched.push(cycle),
+      }
+    }
+
+    let mut diff = Diff::default();
+    for ring in rings.into_iter() {
+      let key = cycle_key(&ring.data);
+      if let Some(mut cycle) = old.get_mut(&key).and_then(|c| c.pop()) {
+        cycle.key = Some(key);
+        self.cycles.push(cycle);
+        continue;
+      }
+      let (positions, indices) = triangulate_simple_with(&mut ring.data.clone(), 2, self.backend);
+      let ids: Vec<usize> = (self.next_id..self.next_id+indices.len()/3).collect();
+      self.next_id += ids.len();
+      let cycle = Cycle::new(key, positions, indices, ids);
+      diff.added.extend(cycle.triangles());
+      self.cycles.push(cycle);
+    }
+    for cycle in old.values().flatten().chain(patched.iter()) {
+      diff.removed.extend(cycle.ids.iter().copied().filter(|&id| id!=NONE));
+    }
+    diff.removed.sort_unstable();
+    self.outline = self.simple_outline();
+    diff
+  }
+
+  /// The outline as points of its cycle, if it is a single simple one which goes through all of the outline's points in turn.
+  fn simple_outline(&self) -> Option<Outline> {
+    let n = self.len();
+    let [cycle] = &self.cycles[..] else {
+      return None;
+    };
+    let m = cycle.positions.len()/2;
+    if m!=n || cycle.indices.len()/3+2!=n {
+      return None;
+    }
+    let bits = |p: &[f64]| (p[0].to_bits(), p[1].to_bits());
+    let at: HashMap<(u64, u64), usize> = cycle.positions.chunks_exact(2).enumerate().map(|(v, p)| (bits(p), v)).collect();
+    let slots: Vec<usize> = self.data.chunks_exact(2).map(|p| at.get(&bits(p)).copied()).collect::<Option<Vec<usize>>>()?;
+    // going round the cycle one way or the other
+    let step = (slots[1]+m-slots[0])%m;
+    if (step!=1 && step!=m-1) || (0..n).any(|i| (slots[(i+1)%n]+m-slots[i])%m!=step) {
+      return None;
+    }
+    let mut grid = SegmentGrid::new(&self.data);
+    for i in 0..n {
+      let j = (i+1)%n;
+      grid.insert((slots[i], slots[j]), self.point(i), self.point(j));
+    }
+    Some(Outline { slots, grid })
+  }
+
+  /// Patches an edit into a simple outline, or resolves the outline again.
+  fn update_at(&mut self, edit: Edit) -> Diff {
+    match self.patch(edit) {
+      Some(diff) => diff,
+      None => self.update(),
+    }
+  }
+
+  /// Triangulates again only around an edit of a simple outline which leaves it simple, i.e. whose new segments meet no other.
+  ///
+  /// The area which changed lies in the triangles of the edited point (before and after the edit) with its two neighbours.
+  /// The triangles at the edited point, and the ones joined to them overlapping that area, are a connected part
+  /// of the cycle's triangulation; that part is a simple polygon of its points, and is triangulated again.
+  /// It is found through the neighbours of each triangle, so an edit only touches the triangles and segments around it.
+  fn patch(&mut self, edit: Edit) -> Option<Diff> {
+    let n = self.len();
+    let outline = self.outline.as_ref()?;
+    if n<4 {
+      return None;
+    }
+
+    // slots of the points a and b on either side of the edited point, which is at slot `at` (NONE when it is new),
+    // and of the points before a and after b
+    let (slots, old) = (&outline.slots, outline.slots.len());
+    let (i, ia, at, ib) = match edit {
+      Edit::Insert(i) => (i, (i+old-1)%old, NONE, i%old),
+      Edit::Move(i) | Edit::Remove(i) => (i, (i+old-1)%old, slots[i], (i+1)%old),
+    };
+    let (a, b, before, after) = (slots[ia], slots[ib], slots[(ia+old-1)%old], slots[(ib+1)%old]);
+    let cycle = &self.cycles[0];
+    let point = |v: usize| if v==at { self.point(i) } else { cycle.point(v) };
+    // the segments replaced, and the one next to each new segment, are only checked for running back along it
+    let clear = match edit {
+      Edit::Insert(_) | Edit::Move(_) => {
+        let q = self.point(i);
+        segment_is_clear(&outline.grid, cycle, cycle.point(before), (cycle.point(a), q), cycle.point(b), &[(before, a), (a, b), (a, at), (at, b)])
+          && segment_is_clear(&outline.grid, cycle, cycle.point(a), (q, cycle.point(b)), cycle.point(after), &[(a, b), (a, at), (at, b), (b, after)])
+      },
+      Edit::Remove(_) => segment_is_clear(&outline.grid, cycle, cycle.point(before), (cycle.point(a), cycle.point(b)), cycle.point(after), &[(before, a), (a, at), (at, b), (b, after)]),
+    };
+    if !clear {
+      return None;
+    }
+
+    // triangles with the edited point (for an insertion, the one on the segment it splits),
+    // and the ones next to them overlapping the area which changed
+    let (pa, pb) = (cycle.point(a), cycle.point(b));
+    let changed: Vec<[(f64, f64); 3]> = match edit {
+      Edit::Insert(_) => vec![[pa, self.point(i), pb]],
+      Edit::Move(_) => vec![[pa, cycle.point(at), pb], [pa, self.point(i), pb]],
+      Edit::Remove(_) => vec![[pa, cycle.point(at), pb]],
+    };
+    let seeds: Vec<usize> = match edit {
+      Edit::Insert(_) => cycle.edges_from(a).into_iter().map(|h| h/3).filter(|&k| cycle.indices[k*3..k*3+3].contains(&b)).collect(),
+      _ => cycle.edges_from(at).into_iter().map(|h| h/3).collect(),
+    };
+    if seeds.is_empty() {
+      return None;
+    }
+    let triangle = |k: usize| [cycle.point(cycle.indices[k*3]), cycle.point(cycle.indices[k*3+1]), cycle.point(cycle.indices[k*3+2])];
+    let mut part: HashSet<usize> = seeds.iter().copied().collect();
+    let mut queue = VecDeque::from(seeds);
+    while let Some(k) = queue.pop_front() {
+      for h in k*3..k*3+3 {
+        let next = cycle.twins[h];
+        if next!=NONE && !part.contains(&(next/3)) && changed.iter().any(|c| !separated(&triangle(next/3), c)) {
+          part.insert(next/3);
+          queue.push_back(next/3);
+        }
+      }
+    }
+
+    // the outline of the part, from a round its edges; it has two points more than triangles if it is a simple polygon
+    let mut outward: HashMap<usize, usize> = HashMap::with_capacity(part.len()+2);
+    for &k in part.iter() {
+      for h in k*3..k*3+3 {
+        let across = cycle.twins[h];
+        if (across==NONE || !part.contains(&(across/3))) && outward.insert(cycle.indices[h], h).is_some() {
+          return None;
+        }
+      }
+    }
+    if outward.len()!=part.len()+2 {
+      return None;
+    }
+    let mut ring: Vec<usize> = vec![a];
+    loop {
+      let v = cycle.indices[next_edge(*outward.get(ring.last()?)?)];
+      if v==a {
+        break;
+      }
+      if ring.len()==outward.len() {
+        return None;
+      }
+      ring.push(v);
+    }
+    if ring.len()!=outward.len() {
+      return None;
+    }
+    // the edges around the part, to join the new triangles to the triangles across them
+    let around: HashMap<(usize, usize), usize> = outward.values().map(|&h| ((cycle.indices[h], cycle.indices[next_edge(h)]), cycle.twins[h])).collect();
+
+    // with the edit applied
+    match edit {
+      Edit::Insert(_) => {
+        let m = ring.len();
+        let k = (0..m).find(|&k| (ring[k], ring[(k+1)%m])==(a, b) || (ring[k], ring[(k+1)%m])==(b, a))?;
+        ring.insert(k+1, NONE);
+      },
+      Edit::Move(_) => {},
+      Edit::Remove(_) => ring.retain(|&v| v!=at),
+    }
+    let data: Vec<f64> = ring.iter().flat_map(|&v| {
+      let (x, y) = point(v);
+      [x, y]
+    }).collect();
+    let (positions, indices) = triangulate_simple_with(&mut data.clone(), 2, self.backend);
+    if positions!=data || indices.len()/3+2!=ring.len() {
+      return None;
+    }
+
+    // put the edit and the new triangles in place of the part's
+    let mut removed: Vec<usize> = part.into_iter().collect();
+    removed.sort_unstable();
+    let EditablePolygon { data, cycles, outline, next_id, .. } = self;
+    let (cycle, outline) = (&mut cycles[0], outline.as_mut()?);
+    let mut diff = Diff { removed: removed.iter().map(|&k| cycle.ids[k]).collect(), added: Vec::new() };
+    diff.removed.sort_unstable();
+    match edit {
+      Edit::Insert(_) => outline.grid.remove((a, b), pa, pb),
+      _ => {
+        let from = cycle.point(at);
+        outline.grid.remove((a, at), pa, from);
+        outline.grid.remove((at, b), from, pb);
+      },
+    }
+    let v = match edit {
+      Edit::Insert(i) => {
+        let v = cycle.add_point((data[i*2], data[i*2+1]));
+        outline.slots.insert(i, v);
+        v
+      },
+      Edit::Move(i) => {
+        cycle.positions[at*2..at*2+2].copy_from_slice(&data[i*2..i*2+2]);
+        at
+      },
+      Edit::Remove(i) => {
+        cycle.free_points.push(at);
+        cycle.corners[at] = NONE;
+        outline.slots.remove(i);
+        NONE
+      },
+    };
+    match edit {
+      Edit::Remove(_) => outline.grid.insert((a, b), pa, pb),
+      _ => {
+        let q = cycle.point(v);
+        outline.grid.insert((a, v), pa, q);
+        outline.grid.insert((v, b), q, pb);
+      },
+    }
+
+    for &k in removed.iter().rev() {
+      cycle.ids[k] = NONE;
+      cycle.free_triangles.push(k);
+    }
+    // unlink the triangles around the part, to link them to the new triangles with the same edges;
+    // an edge which no new triangle has (as when an ear is removed) is on the cycle now, and its triangle may be the last at its points
+    for &twin in around.values().filter(|&&twin| twin!=NONE) {
+      cycle.twins[twin] = NONE;
+      cycle.corners[cycle.indices[twin]] = twin;
+      cycle.corners[cycle.indices[next_edge(twin)]] = next_edge(twin);
+    }
+    let mut added: Vec<usize> = Vec::with_capacity(indices.len()/3);
+    let mut edges: HashMap<(usize, usize), usize> = HashMap::with_capacity(indices.len());
+    for t in indices.chunks_exact(3) {
+      let k = cycle.add_triangle();
+      for e in 0..3 {
+        let u = if ring[t[e]]==NONE { v } else { ring[t[e]] };
+        cycle.indices[k*3+e] = u;
+        cycle.corners[u] = k*3+e;
+      }
+      for h in k*3..k*3+3 {
+        edges.insert((cycle.indices[h], cycle.indices[next_edge(h)]), h);
+      }
+      cycle.ids[k] = *next_id;
+      *next_id += 1;
+      added.push(k);
+    }
+    for &k in added.iter() {
+      for h in k*3..k*3+3 {
+        let (u, w) = (cycle.indices[h], cycle.indices[next_edge(h)]);
+        cycle.twins[h] = match edges.get(&(w, u)) {
+          Some(&twin) => twin,
+          None => {
+            let twin = around.get(&(u, w)).copied().unwrap_or(NONE);
+            if twin!=NONE {
+              cycle.twins[twin] = h;
+            }
+            twin
+          },
+        };
+      }
+    }
+    cycle.key = None;
+    diff.added = added.iter().map(|&k| cycle.triangle(k)).collect();
+    Some(diff)
+  }
+}
+
+/// Uniform grid over the segments of an outline, like [`TriangleIndex`](crate::locate::TriangleIndex) over triangles,
+/// so that a new segment is only checked against the segments near it.
+/// Segments are kept as the slots of their points; points beyond the grid go to its border cells.
+struct SegmentGrid {
+  x0: f64,
+  y0: f64,
+  cell: f64,
+  cols: usize,
+  rows: usize,
+  cells: Vec<Vec<(usize, usize)>>,
+}
+
+impl SegmentGrid {
+  /// An empty grid over the bbox of an outline (with dim 2), with about one segment per cell.
+  fn new(data: &[f64]) -> SegmentGrid {
+    let n = data.len()/2;
+    let (mut x0, mut y0, mut x1, mut y1) = (f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for p in data.chunks_exact(2) {
+      x0 = x0.min(p[0]);
+      y0 = y0.min(p[1]);
+      x1 = x1.max(p[0]);
+      y1 = y1.max(p[1]);
+    }
+    if n==0 {
+      (x0, y0, x1, y1) = (0., 0., 0., 0.);
+    }
+    let (w, h) = (x1-x0, y1-y0);
+    let cell = if w*h>0. { (w*h/n as f64).sqrt() } else { w.max(h).max(1.) };
+    let cell = cell.max(w.max(h)/n.max(1) as f64);
+    let cols = ((w/cell).ceil() as usize).max(1);
+    let rows = ((h/cell).ceil() as usize).max(1);
+    SegmentGrid { x0, y0, cell, cols, rows, cells: vec![Vec::new(); cols*rows] }
+  }
+
+  /// Cells overlapped by the bbox of segment u-w: (col0, row0, col1, row1).
+  fn cell_range(&self, (ux, uy): (f64, f64), (wx, wy): (f64, f64)) -> (usize, usize, usize, usize) {
+    let col = |x: f64| (((x-self.x0)/self.cell).max(0.) as usize).min(self.cols-1);
+    let row = |y: f64| (((y-self.y0)/self.cell).max(0.) as usize).min(self.rows-1);
+    (col(ux.min(wx)), row(uy.min(wy)), col(ux.max(wx)), row(uy.max(wy)))
+  }
+
+  fn insert(&mut self, segment: (usize, usize), u: (f64, f64), w: (f64, f64)) {
+    let (c0, r0, c1, r1) = self.cell_range(u, w);
+    for r in r0..=r1 {
+      for c in c0..=c1 {
+        self.cells[r*self.cols+c].push(segment);
+      }
+    }
+  }
+
+  /// Removes a segment, which must be where it was inserted.
+  fn remove(&mut self, segment: (usize, usize), u: (f64, f64), w: (f64, f64)) {
+    let (c0, r0, c1, r1) = self.cell_range(u, w);
+    for r in r0..=r1 {
+      for c in c0..=c1 {
+        let cell = &mut self.cells[r*self.cols+c];
+        if let Some(k) = cell.iter().position(|&s| s==segment) {
+          cell.swap_remove(k);
+        }
+      }
+    }
+  }
+
+  /// The segments in the cells overlapped by the bbox of segment u-w.
+  fn near(&self, u: (f64, f64), w: (f64, f64)) -> Vec<(usize, usize)> {
+    let (c0, r0, c1, r1) = self.cell_range(u, w);
+    let mut segments: Vec<(usize, usize)> = (r0..=r1).flat_map(|r| (c0..=c1).map(move |c| r*self.cols+c))
+      .flat_map(|k| self.cells[k].iter().copied()).collect();
+    segments.sort_unstable();
+    segments.dedup();
+    segments
+  }
+}
+
+/// Whether segment u-w meets none of the segments of an outline but the ones in `skip` (next to it, or replaced by it),
+/// and its neighbours, from `before` to u and from w to `after`, don't run back along it.
+fn segment_is_clear(grid: &SegmentGrid, cycle: &Cycle, before: (f64, f64), ((ux, uy), (wx, wy)): ((f64, f64), (f64, f64)), after: (f64, f64), skip: &[(usize, usize)]) -> bool {
+  let (cx, cy) = before;
+  if side(ux, uy, wx, wy, cx, cy).abs()<=ROUNDING && (cx-ux)*(wx-ux) + (cy-uy)*(wy-uy) > 0. {
+    return false;
+  }
+  let (dx, dy) = after;
+  if side(wx, wy, ux, uy, dx, dy).abs()<=ROUNDING && (dx-wx)*(ux-wx) + (dy-wy)*(uy-wy) > 0. {
+    return false;
+  }
+  grid.near((ux, uy), (wx, wy)).into_iter().filter(|s| !skip.contains(s)).all(|(s, t)| {
+    let ((cx, cy), (dx, dy)) = (cycle.point(s), cycle.point(t));
+    if ux.max(wx)<cx.min(dx) || cx.max(dx)<ux.min(wx) || uy.max(wy)<cy.min(dy) || cy.max(dy)<uy.min(wy) {
+      true
+    } else {
+      let apart = |s: f64, t: f64| (s>ROUNDING && t>ROUNDING) || (s< -ROUNDING && t< -ROUNDING);
+      apart(side(ux, uy, wx, wy, cx, cy), side(ux, uy, wx, wy, dx, dy)) || apart(side(cx, cy, dx, dy, ux, uy), side(cx, cy, dx, dy, wx, wy))
+    }
+  })
+}
+
+/// Whether two triangles have no area in common: a side of one has the other on or beyond it.
+/// A flat triangle is separated only by the sides of the other, so that one lying across the other is not.
+fn separated(t: &[(f64, f64); 3], u: &[(f64, f64); 3]) -> bool {
+  let beyond = |t: &[(f64, f64); 3], u: &[(f64, f64); 3]| {
+    let cross = |(ax, ay): (f64, f64), (bx, by): (f64, f64), (qx, qy): (f64, f64)| (bx-ax)*(qy-ay) - (by-ay)*(qx-ax);
+    let turn = cross(t[0], t[1], t[2]);
+    turn!=0. && (0..3).any(|e| u.iter().all(|&q| cross(t[e], t[(e+1)%3], q)*turn<=0.))
+  };
+  beyond(t, u) || beyond(u, t)
+}
+
+/// Bits of the points of a ring, starting from the same point wherever the ring was started.
+fn cycle_key(ring: &[f64]) -> Vec<u64> {
+  let mut points: Vec<(f64, f64)> = ring.chunks_exact(2).map(|p| (p[0], p[1])).collect();
+  rotate_to_start(&mut points);
+  points.into_iter().flat_map(|(x, y)| [x.to_bits(), y.to_bits()]).collect()
+}
@@ -0,0 +1,422 @@
+//! Mesh export
+//!
+//! Writes a triangulation result (`new_data`, `indices`) as Wavefront OBJ, PLY (ascii/binary) or STL (ascii/binary).
+//! Coordinates with `dim` 2 get z=0; with `dim` 3 or more, the third component is taken as z.
+//!
+//! The `read_*` functions parse those files back into a coordinates array with dim 3 and an index array.
+//! They cover what the writers produce (and the common variants of the formats), not every extension of them.
+//!
+//! # Ex
+//! ```rust
+//! use louvre::{triangulate, export::*};
+//!
+//! let mut data: Vec<f64> = vec![0.,0., 1.,0., 1.,1., 0.,1.];
+//! let (new_data, indices) = triangulate(&mut data, 2);
+//! let mut obj: Vec<u8> = Vec::new();
+//! write_obj(&mut obj, &new_data, 2, &indices).unwrap();
+//! let (positions, faces) = read_obj(&obj).unwrap();
+//! assert_eq!(positions.len(), new_data.len()/2*3);
+//! assert_eq!(faces, indices);
+//! ```
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+
+/// Ascii or binary encoding of PLY and STL.
+/// Binary PLY is written in little endian.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+  Ascii,
+  Binary,
+}
+
+
+fn point(new_data: &[f64], dim: usize, i: usize) -> [f64; 3] {
+  let z = if dim>=3 { new_data[i*dim+2] } else { 0. };
+  [new_data[i*dim], new_data[i*dim+1], z]
+}
+
+fn invalid(msg: &str) -> io::Error {
+  io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+/// Unit normal of a triangle by the right hand rule; zero for a degenerate triangle.
+fn normal(a: [f64; 3], b: [f64; 3], c: [f64; 3]) -> [f64; 3] {
+  let u = [b[0]-a[0], b[1]-a[1], b[2]-a[2]];
+  let v = [c[0]-a[0], c[1]-a[1], c[2]-a[2]];
+  let n = [u[1]*v[2]-u[2]*v[1], u[2]*v[0]-u[0]*v[2], u[0]*v[1]-u[1]*v[0]];
+  let len = (n[0]*n[0]+n[1]*n[1]+n[2]*n[2]).sqrt();
+  if len>0. { [n[0]/len, n[1]/len, n[2]/len] } else { [0., 0., 0.] }
+}
+
+
+// ----- OBJ ----- //
+
+/// Writes a Wavefront OBJ with `v` and (1-based) `f` lines.
+pub fn write_obj<W: Write>(w: &mut W, new_data: &[f64], dim: usize, indices: &[usize]) -> io::Result<()> {
+  writeln!(w, "# louvre")?;
+  for i in 0..(new_data.len()/dim) {
+    let [x, y, z] = point(new_data, dim, i);
+    writeln!(w, "v {} {} {}", x, y, z)?;
+  }
+  for t in indices.chunks_exact(3) {
+    writeln!(w, "f {} {} {}", t[0]+1, t[1]+1, t[2]+1)?;
+  }
+  Ok(())
+}
+
+/// Reads `v` and `f` lines of a Wavefront OBJ.
+/// Faces with texture/normal refs (`f 1/1/1 ...`) and negative indices are accepted;
+/// faces of more than 3 vertices are split into a fan.
+pub fn read_obj(bytes: &[u8]) -> io::Result<(Vec<f64>, Vec<usize>)> {
+  let text = std::str::from_utf8(bytes).map_err(|_| invalid("obj is not utf-8"))?;
+  let mut positions: Vec<f64> = Vec::new();
+  let mut indices: Vec<usize> = Vec::new();
+  for line in text.lines() {
+    let mut words = line.split_whitespace();
+    match words.next() {
+      Some("v") => {
+        let v: Vec<f64> = words.take(3).map(|x| x.parse::<f64>()).collect::<Result<_, _>>()
+          .map_err(|_| invalid("obj vertex"))?;
+        if v.len()<2 {
+          return Err(invalid("obj vertex"));
+        }
+        positions.extend([v[0], v[1], v.get(2).copied().unwrap_or(0.)]);
+      },
+      Some("f") => {
+        let n = (positions.len()/3) as i64;
+        let face: Vec<usize> = words.map(|x| {
+          let k: i64 = x.split('/').next().unwrap_or("").parse().map_err(|_| invalid("obj face"))?;
+          let k = if k<0 { n+k } else { k-1 };
+          if k<0 || k>=n { Err(invalid("obj face index out of range")) } else { Ok(k as usize) }
+        }).collect::<Result<_, _>>()?;
+        for j in 2..face.len() {
+          indices.extend([face[0], face[j-1], face[j]]);
+        }
+      },
+      _ => {},
+    }
+  }
+  Ok((positions, indices))
+}
+
+
+// ----- PLY ----- //
+
+/// Writes a PLY with double precision `x y z` vertices and `vertex_indices` faces.
+pub fn write_ply<W: Write>(w: &mut W, new_data: &[f64], dim: usize, indices: &[usize], encoding: Encoding) -> io::Result<()> {
+  let n = new_data.len()/dim;
+  // faces are written as `uint` lists
+  if indices.iter().any(|&i| u32::try_from(i).is_err()) {
+    return Err(invalid("ply face index beyond u32"));
+  }
+  let format = match encoding { Encoding::Ascii => "ascii", Encoding::Binary => "binary_little_endian" };
+  write!(w, "ply\nformat {} 1.0\ncomment louvre\n", format)?;
+  write!(w, "element vertex {}\nproperty double x\nproperty double y\nproperty double z\n", n)?;
+  write!(w, "element face {}\nproperty list uchar uint vertex_indices\nend_header\n", indices.len()/3)?;
+  match encoding {
+    Encoding::Ascii => {
+      for i in 0..n {
+        let [x, y, z] = point(new_data, dim, i);
+        writeln!(w, "{} {} {}", x, y, z)?;
+      }
+      for t in indices.chunks_exact(3) {
+        writeln!(w, "3 {} {} {}", t[0], t[1], t[2])?;
+      }
+    },
+    Encoding::Binary => {
+      for i in 0..n {
+        for c in point(new_data, dim, i) {
+          w.write_all(&c.to_le_bytes())?;
+        }
+      }
+      for t in indices.chunks_exact(3) {
+        w.write_all(&[3])?;
+        for &i in t {
+          w.write_all(&(i as u32).to_le_bytes())?;
+        }
+      }
+    },
+  }
+  Ok(())
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum PlyFormat { Ascii, Le, Be }
+
+#[derive(Clone, Copy)]
+enum PlyType { I8, U8, I16, U16, I32, U32, F32, F64 }
+
+impl PlyType {
+  fn parse(s: &str) -> io::Result<PlyType> {
+    Ok(match s {
+      "char" | "int8" => PlyType::I8,
+      "uchar" | "uint8" => PlyType::U8,
+      "short" | "int16" => PlyType::I16,
+      "ushort" | "uint16" => PlyType::U16,
+      "int" | "int32" => PlyType::I32,
+      "uint" | "uint32" => PlyType::U32,
+      "float" | "float32" => PlyType::F32,
+      "double" | "float64" => PlyType::F64,
+      _ => return Err(invalid("ply property type")),
+    })
+  }
+
+  fn size(&self) -> usize {
+    match self {
+      PlyType::I8 | PlyType::U8 => 1,
+      PlyType::I16 | PlyType::U16 => 2,
+      PlyType::I32 | PlyType::U32 | PlyType::F32 => 4,
+      PlyType::F64 => 8,
+    }
+  }
+}
+
+struct PlyProperty {
+  name: String,
+  /// type of the list count, if it is a list.
+  count: Option<PlyType>,
+  kind: PlyType,
+}
+
+struct PlyElement {
+  name: String,
+  len: usize,
+  properties: Vec<PlyProperty>,
+}
+
+/// Reads the values of a PLY body one by one, whatever the format is.
+struct PlyScanner<'a> {
+  bytes: &'a [u8],
+  i: usize,
+  format: PlyFormat,
+}
+
+impl PlyScanner<'_> {
+  /// Bytes left to read.
+  fn left(&self) -> usize {
+    self.bytes.len()-self.i
+  }
+
+  /// The fewest bytes a value takes: its size, or a character in ascii.
+  fn least_size(&self, kind: PlyType) -> usize {
+    if self.format==PlyFormat::Ascii { 1 } else { kind.size() }
+  }
+
+  fn value(&mut self, kind: PlyType) -> io::Result<f64> {
+    if self.format==PlyFormat::Ascii {
+      while self.i<self.bytes.len() && self.bytes[self.i].is_ascii_whitespace() {
+        self.i += 1;
+      }
+      let start = self.i;
+      while self.i<self.bytes.len() && !self.bytes[self.i].is_ascii_whitespace() {
+        self.i += 1;
+      }
+      return std::str::from_utf8(&self.bytes[start..self.i]).ok()
+        .and_then(|s| s.parse::<f64>().ok())
+        .ok_or(invalid("ply ascii value"));
+    }
+    let n = kind.size();
+    if self.i+n>self.bytes.len() {
+      return Err(invalid("unexpected end of ply"));
+    }
+    let mut b = [0u8; 8];
+    b[..n].copy_from_slice(&self.bytes[self.i..self.i+n]);
+    if self.format==PlyFormat::Be {
+      b[..n].reverse();
+    }
+    self.i += n;
+    Ok(match kind {
+      PlyType::I8 => b[0] as i8 as f64,
+      PlyType::U8 => b[0] as f64,
+      PlyType::I16 => i16::from_le_bytes([b[0], b[1]]) as f64,
+      PlyType::U16 => u16::from_le_bytes([b[0], b[1]]) as f64,
+      PlyType::I32 => i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+      PlyType::U32 => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+      PlyType::F32 => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+      PlyType::F64 => f64::from_le_bytes(b),
+    })
+  }
+}
+
+/// Reads the `x y z` of the `vertex` element and the `vertex_indices` (or `vertex_index`) of the `face` element of a PLY.
+/// Faces of more than 3 vertices are split into a fan; other elements and properties are skipped.
+pub fn read_ply(bytes: &[u8]) -> io::Result<(Vec<f64>, Vec<usize>)> {
+  // ----- header ----- //
+  const END: &[u8] = b"end_header";
+  let end = bytes.windows(END.len()).position(|w| w==END).ok_or(invalid("ply without end_header"))?;
+  let mut body = end+END.len();
+  while body<bytes.len() && bytes[body]!=b'\n' {
+    body += 1;
+  }
+  let header = std::str::from_utf8(&bytes[..end]).map_err(|_| invalid("ply header is not utf-8"))?;
+
+  let mut format: Option<PlyFormat> = None;
+  let mut elements: Vec<PlyElement> = Vec::new();
+  for (k, line) in header.lines().enumerate() {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    match words.as_slice() {
+      ["ply"] if k==0 => {},
+      _ if k==0 => return Err(invalid("not a ply")),
+      ["format", f, _] => {
+        format = Some(match *f {
+          "ascii" => PlyFormat::Ascii,
+          "binary_little_endian" => PlyFormat::Le,
+          "binary_big_endian" => PlyFormat::Be,
+          _ => return Err(invalid("ply format")),
+        });
+      },
+      ["element", name, len] => {
+        let len = len.parse().map_err(|_| invalid("ply element"))?;
+        elements.push(PlyElement{ name: name.to_string(), len, properties: Vec::new() });
+      },
+      ["property", "list", count, kind, name] => {
+        let e = elements.last_mut().ok_or(invalid("ply property without element"))?;
+        e.properties.push(PlyProperty{ name: name.to_string(), count: Some(PlyType::parse(count)?), kind: PlyType::parse(kind)? });
+      },
+      ["property", kind, name] => {
+        let e = elements.last_mut().ok_or(invalid("ply property without element"))?;
+        e.properties.push(PlyProperty{ name: name.to_string(), count: None, kind: PlyType::parse(kind)? });
+      },
+      _ => {},
+    }
+  }
+  let format = format.ok_or(invalid("ply without format"))?;
+
+  // ----- body ----- //
+  let mut s = PlyScanner{ bytes, i: (body+1).min(bytes.len()), format };
+  let mut positions: Vec<f64> = Vec::new();
+  let mut indices: Vec<usize> = Vec::new();
+  for e in elements.iter() {
+    // the count comes from the input, so check it against the bytes left: a row takes at least a value per property.
+    let row: usize = e.properties.iter().map(|p| s.least_size(p.count.unwrap_or(p.kind))).sum();
+    if e.len>0 && (row==0 || e.len>s.left()/row) {
+      return Err(invalid("unexpected end of ply"));
+    }
+    for _ in 0..e.len {
+      let mut p = [0.; 3];
+      for prop in e.properties.iter() {
+        match prop.count {
+          None => {
+            let x = s.value(prop.kind)?;
+            if e.name=="vertex" {
+              match prop.name.as_str() { "x" => p[0] = x, "y" => p[1] = x, "z" => p[2] = x, _ => {} }
+            }
+          },
+          Some(count) => {
+            let n = s.value(count)? as usize;
+            if n>s.left()/s.least_size(prop.kind) {
+              return Err(invalid("unexpected end of ply"));
+            }
+            let list: Vec<f64> = (0..n).map(|_| s.value(prop.kind)).collect::<Result<_, _>>()?;
+            if e.name=="face" && (prop.name=="vertex_indices" || prop.name=="vertex_index") {
+              for j in 2..n {
+                indices.extend([list[0] as usize, list[j-1] as usize, list[j] as usize]);
+              }
+            }
+          },
+        }
+      }
+      if e.name=="vertex" {
+        positions.extend(p);
+      }
+    }
+  }
+  if indices.iter().any(|&i| i*3>=positions.len()) {
+    return Err(invalid("ply face index out of range"));
+  }
+  Ok((positions, indices))
+}
+
+
+// ----- STL ----- //
+
+/// Writes an STL with facet normals by the right hand rule.
+/// Binary STL stores single precision floats.
+pub fn write_stl<W: Write>(w: &mut W, new_data: &[f64], dim: usize, indices: &[usize], encoding: Encoding) -> io::Result<()> {
+  match encoding {
+    Encoding::Ascii => {
+      writeln!(w, "solid louvre")?;
+      for t in indices.chunks_exact(3) {
+        let (a, b, c) = (point(new_data, dim, t[0]), point(new_data, dim, t[1]), point(new_data, dim, t[2]));
+        let n = normal(a, b, c);
+        writeln!(w, "facet normal {} {} {}\nouter loop", n[0], n[1], n[2])?;
+        for p in [a, b, c] {
+          writeln!(w, "vertex {} {} {}", p[0], p[1], p[2])?;
+        }
+        writeln!(w, "endloop\nendfacet")?;
+      }
+      writeln!(w, "endsolid louvre")?;
+    },
+    Encoding::Binary => {
+      let mut header = [0u8; 80];
+      header[..6].copy_from_slice(b"louvre");
+      w.write_all(&header)?;
+      w.write_all(&((indices.len()/3) as u32).to_le_bytes())?;
+      for t in indices.chunks_exact(3) {
+        let (a, b, c) = (point(new_data, dim, t[0]), point(new_data, dim, t[1]), point(new_data, dim, t[2]));
+        for p in [normal(a, b, c), a, b, c] {
+          for x in p {
+            w.write_all(&(x as f32).to_le_bytes())?;
+          }
+        }
+        w.write_all(&[0, 0])?;
+      }
+    },
+  }
+  Ok(())
+}
+
+/// Reads an ascii or binary STL. Facet normals are ignored;
+/// vertices with exactly the same coordinates are merged to make the index array.
+pub fn read_stl(bytes: &[u8]) -> io::Result<(Vec<f64>, Vec<usize>)> {
+  let mut positions: Vec<f64> = Vec::new();
+  let mut indices: Vec<usize> = Vec::new();
+  let mut merged: HashMap<[u64; 3], usize> = HashMap::new();
+  let mut push = |p: [f64; 3]| {
+    let key = p.map(|x| (x+0.).to_bits()); // +0. folds -0. into 0.
+    let i = *merged.entry(key).or_insert_with(|| {
+      positions.extend(p);
+      positions.len()/3-1
+    });
+    indices.push(i);
+  };
+
+  // A binary STL may begin with "solid" as well, so its size decides.
+  let binary = bytes.len()>=84 && {
+    let n = u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]) as usize;
+    bytes.len()==84+n*50
+  };
+  if binary {
+    for facet in bytes[84..].chunks_exact(50) {
+      for v in 1..4 {
+        let p: [f64; 3] = std::array::from_fn(|k| {
+          let j = v*12+k*4;
+          f32::from_le_bytes([facet[j], facet[j+1], facet[j+2], facet[j+3]]) as f64
+        });
+        push(p);
+      }
+    }
+  } else {
+    let text = std::str::from_utf8(bytes).map_err(|_| invalid("stl is neither binary nor ascii"))?;
+    if !text.trim_start().starts_with("solid") {
+      return Err(invalid("not an stl"));
+    }
+    for line in text.lines() {
+      let mut words = line.split_whitespace();
+      if words.next()==Some("vertex") {
+        let v: Vec<f64> = words.take(3).map(|x| x.parse::<f64>()).collect::<Result<_, _>>()
+          .map_err(|_| invalid("stl vertex"))?;
+        if v.len()<3 {
+          return Err(invalid("stl vertex"));
+        }
+        push([v[0], v[1], v[2]]);
+      }
+    }
+    if indices.len()%3!=0 {
+      return Err(invalid("stl facet without 3 vertices"));
+    }
+  }
+  Ok((positions, indices))
+}
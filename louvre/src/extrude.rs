@@ -0,0 +1,136 @@
+//! Extrusion into prisms
+//!
+//! Extrudes a polygon between two heights into a closed 3D mesh: a bottom cap, a top cap and side walls.
+//!
+//! The caps are the triangulation of the polygon, so self-intersecting polygons are resolved the same way
+//! [`triangulate`] resolves them (via `decomp_simples`).
+//! The walls run along the boundary of the caps: the cap edges which aren't cancelled by an opposite edge of a neighbouring triangle.
+//! Hence cycles touching at intersection points, or holes linked by bridges, get walls only where the region really ends.
+
+use crate::*;
+use std::collections::HashMap;
+
+
+/// Extruded mesh. Coordinates arrays are dim 3.
+///
+/// Vertices are split per face so that each face has its own flat normal.
+/// Use [`Extrusion::welded`] for a mesh sharing its vertices.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Extrusion {
+  pub positions: Vec<f64>,
+  /// Outward unit normal of each vertex.
+  pub normals: Vec<f64>,
+  /// Triangles, counter-clockwise seen from the outside.
+  pub indices: Vec<usize>,
+}
+
+impl Extrusion {
+  /// Merges vertices with the same position.
+  /// The result is watertight: each edge is used as many times in one direction as in the other.
+  /// (Just once each, except for vertical edges where parts of the polygon touch, like at intersection points.)
+  pub fn welded(&self) -> (Vec<f64>, Vec<usize>) {
+    let mut positions: Vec<f64> = Vec::new();
+    let mut merged: HashMap<[u64; 3], usize> = HashMap::new();
+    let indices = self.indices.iter().map(|&i| {
+      let p = [self.positions[i*3], self.positions[i*3+1], self.positions[i*3+2]];
+      *merged.entry(p.map(|x| (x+0.).to_bits())).or_insert_with(|| {
+        positions.extend(p);
+        positions.len()/3-1
+      })
+    }).collect();
+    (positions, indices)
+  }
+}
+
+
+/// Triangulates a polygon and extrudes it from z=`bottom` to z=`top`.
+pub fn extrude(data: &mut Vec<f64>, dim: usize, bottom: f64, top: f64) -> Extrusion {
+  if data.len()<dim*3 {
+    return extrude_mesh(&[], &[], bottom, top);
+  }
+  let (new_data, indices) = triangulate(data, dim);
+  extrude_mesh(&new_data, &indices, bottom, top)
+}
+
+/// Extrudes a polygon with holes (see [`triangulate_polygon`]) from z=`bottom` to z=`top`.
+pub fn extrude_polygon(rings: &[Vec<f64>], dim: usize, bottom: f64, top: f64) -> Extrusion {
+  let (new_data, indices) = triangulate_polygon(rings, dim);
+  extrude_mesh(&new_data, &indices, bottom, top)
+}
+
+/// Extrudes a 2d triangulation (counter-clockwise triangles, as louvre makes) from z=`bottom` to z=`top`.
+pub fn extrude_mesh(new_data: &[f64], indices: &[usize], bottom: f64, top: f64) -> Extrusion {
+  let mut e = Extrusion{ positions: Vec::new(), normals: Vec::new(), indices: Vec::new() };
+  let (bottom, top) = (bottom.min(top), bottom.max(top));
+  if indices.len()<3 || bottom==top {
+    return e;
+  }
+
+  let push = |e: &mut Extrusion, x: f64, y: f64, z: f64, n: [f64; 3]| {
+    e.positions.extend([x, y, z]);
+    e.normals.extend(n);
+    e.positions.len()/3-1
+  };
+
+  // ----- caps ----- //
+  let n = new_data.len()/2;
+  let top0 = e.positions.len()/3;
+  for i in 0..n {
+    push(&mut e, new_data[i*2], new_data[i*2+1], top, [0., 0., 1.]);
+  }
+  let bottom0 = e.positions.len()/3;
+  for i in 0..n {
+    push(&mut e, new_data[i*2], new_data[i*2+1], bottom, [0., 0., -1.]);
+  }
+  for t in indices.chunks_exact(3) {
+    e.indices.extend([top0+t[0], top0+t[1], top0+t[2]]);
+    e.indices.extend([bottom0+t[0], bottom0+t[2], bottom0+t[1]]);
+  }
+
+  // ----- boundary ----- //
+  // Identify the cap vertices by position, then count directed edges; a->b minus b->a remains on the boundary.
+  let mut ids: HashMap<[u64; 2], usize> = HashMap::new();
+  let mut points: Vec<(f64, f64)> = Vec::new();
+  let id: Vec<usize> = (0..n).map(|i| {
+    let (x, y) = (new_data[i*2], new_data[i*2+1]);
+    *ids.entry([(x+0.).to_bits(), (y+0.).to_bits()]).or_insert_with(|| {
+      points.push((x, y));
+      points.len()-1
+    })
+  }).collect();
+
+  let mut edges: HashMap<(usize, usize), i32> = HashMap::new();
+  let mut order: Vec<(usize, usize)> = Vec::new();
+  for t in indices.chunks_exact(3) {
+    for k in 0..3 {
+      let (a, b) = (id[t[k]], id[t[(k+1)%3]]);
+      if a==b {
+        continue;
+      }
+      let key = if a<b { (a, b) } else { (b, a) };
+      let count = edges.entry(key).or_insert_with(|| {
+        order.push(key);
+        0
+      });
+      *count += if a<b { 1 } else { -1 };
+    }
+  }
+
+  // ----- walls ----- //
+  for key in order {
+    let count = edges[&key];
+    let (a, b) = if count>0 { key } else { (key.1, key.0) };
+    let ((ax, ay), (bx, by)) = (points[a], points[b]);
+    let len = ((bx-ax)*(bx-ax) + (by-ay)*(by-ay)).sqrt();
+    // the region is on the left of a->b
+    let normal = [(by-ay)/len, -(bx-ax)/len, 0.];
+    for _ in 0..count.abs() {
+      let a0 = push(&mut e, ax, ay, bottom, normal);
+      let b0 = push(&mut e, bx, by, bottom, normal);
+      let b1 = push(&mut e, bx, by, top, normal);
+      let a1 = push(&mut e, ax, ay, top, normal);
+      e.indices.extend([a0, b0, b1, a0, b1, a1]);
+    }
+  }
+  e
+}
@@ -0,0 +1,509 @@
+//! Fill rules over multiple rings
+//!
+//! Louvre's own pipeline resolves a single ring. When several rings overlap each other (SVG subpaths for instance),
+//! which region is filled depends on a fill rule.
+//! The rings are decomposed into simple cycles the same way as [`triangulate`] decomposes a polygon,
+//! each cycle gets the winding number of the region it bounds, and the boundary of the filled region is traced back
+//! into simple rings, which are triangulated without any further intersection check (see `resolve_winding`).
+//! Booleans, offsets, strokes and polygons with holes resolve their rings the same way.
+
+use crate::*;
+use crate::polygon::{append_mesh, triangulate_resolved, triangulate_resolved_with};
+use crate::sweep::{winding_numbers, Query, Segment};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+
+/// Fill rule deciding whether a region with a certain winding number is inside.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillRule {
+  NonZero,
+  EvenOdd,
+  /// winding number > 0
+  Positive,
+  /// winding number < 0
+  Negative,
+}
+
+impl FillRule {
+  pub fn is_filled(&self, winding: i32) -> bool {
+    match self {
+      FillRule::NonZero => winding!=0,
+      FillRule::EvenOdd => winding%2!=0,
+      FillRule::Positive => winding>0,
+      FillRule::Negative => winding<0,
+    }
+  }
+}
+
+/// Parses the value of SVG `fill-rule`: `nonzero` or `evenodd`.
+impl FromStr for FillRule {
+  type Err = String;
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.trim() {
+      "nonzero" => Ok(FillRule::NonZero),
+      "evenodd" => Ok(FillRule::EvenOdd),
+      _ => Err(format!("unknown fill rule: {}", s)),
+    }
+  }
+}
+
+
+/// Triangulates the region filled by `rings` under the fill rule.
+/// Returns a new coordinates array and a index array of it, the same as [`triangulate`].
+pub fn triangulate_fill(rings: &[Vec<f64>], dim: usize, rule: FillRule) -> (Vec<f64>, Vec<usize>) {
+  let mut new_data: Vec<f64> = Vec::new();
+  let mut indices: Vec<usize> = Vec::new();
+  for polygon in resolve_polygons(rings, dim, rule).iter() {
+    append_mesh(triangulate_resolved(polygon), &mut new_data, &mut indices);
+  }
+  (new_data, indices)
+}
+
+/// Resolves the region filled by `rings` under the fill rule into simple rings (with dim 2).
+/// Outer rings are CCW and holes are CW. Rings don't cross each other, though they may touch at vertices.
+pub fn resolve_rings(rings: &[Vec<f64>], dim: usize, rule: FillRule) -> Vec<Vec<f64>> {
+  if dim<2 {
+    return Vec::new();
+  }
+  let rings: Vec<Vec<f64>> = rings.iter().map(|r| r.chunks_exact(dim).flat_map(|c| [c[0], c[1]]).collect()).collect();
+  resolve_winding(&rings, |w| rule.is_filled(w))
+}
+
+/// Same as [`resolve_rings`], but groups the rings into polygons: an outer ring followed by its holes.
+pub fn resolve_polygons(rings: &[Vec<f64>], dim: usize, rule: FillRule) -> Vec<Vec<Vec<f64>>> {
+  group_polygons(resolve_rings(rings, dim, rule))
+}
+
+/// Groups simple rings (with dim 2) into polygons.
+/// CCW rings are outer rings, and each CW ring goes to the smallest outer ring containing it.
+pub fn group_polygons(rings: Vec<Vec<f64>>) -> Vec<Vec<Vec<f64>>> {
+  let areas: Vec<f64> = rings.iter().map(|r| ring_area(r)).collect();
+  let outers: Vec<usize> = (0..rings.len()).filter(|&i| areas[i]>0.).collect();
+  let mut polygons: Vec<Vec<Vec<f64>>> = outers.iter().map(|&i| vec![rings[i].clone()]).collect();
+
+  for (i, ring) in rings.iter().enumerate() {
+    if areas[i]>=0. || ring.len()<6 {
+      continue;
+    }
+    let mut best: Option<usize> = None;
+    for (e, &o) in outers.iter().enumerate() {
+      if encloses(&rings[o], ring)==Some(true) && best.map_or(true, |b| areas[o]<areas[outers[b]]) {
+        best = Some(e);
+      }
+    }
+    if let Some(b) = best {
+      polygons[b].push(ring.clone());
+    }
+  }
+  polygons
+}
+
+/// Area of a ring with dim 2; positive for CCW.
+fn ring_area(ring: &[f64]) -> f64 {
+  let n = ring.len()/2;
+  let mut sum = 0.;
+  for i in 0..n {
+    let j = (i+1)%n;
+    sum += ring[i*2]*ring[j*2+1] - ring[j*2]*ring[i*2+1];
+  }
+  sum*0.5
+}
+
+/// Is `inner` inside `outer`? Both are simple rings (dim 2) which don't cross each other.
+/// Tested at the midpoint of an edge of `inner` off `outer`; `None` if `inner` runs along `outer` all the way.
+fn encloses(outer: &[f64], inner: &[f64]) -> Option<bool> {
+  let n = inner.len()/2;
+  (0..n).map(|i| {
+    let j = (i+1)%n;
+    ((inner[i*2]+inner[j*2])*0.5, (inner[i*2+1]+inner[j*2+1])*0.5)
+  })
+  .find(|&(px, py)| !point_on_ring(px, py, outer))
+  .map(|(px, py)| point_in_ring(px, py, outer))
+}
+
+/// Is the point on an edge of the ring (dim 2), within rounding?
+fn point_on_ring(px: f64, py: f64, ring: &[f64]) -> bool {
+  let n = ring.len()/2;
+  (0..n).any(|i| {
+    let j = (i+1)%n;
+    let (ax, ay, bx, by) = (ring[i*2], ring[i*2+1], ring[j*2], ring[j*2+1]);
+    let (dx, dy) = (bx-ax, by-ay);
+    let cross = dx*(py-ay) - dy*(px-ax);
+    px>=ax.min(bx) && px<=ax.max(bx) && py>=ay.min(by) && py<=ay.max(by) && cross.abs()<=(dx*dx+dy*dy)*1e-12
+  })
+}
+
+/// Even-odd point in ring test (dim 2).
+fn point_in_ring(px: f64, py: f64, ring: &[f64]) -> bool {
+  let n = ring.len()/2;
+  let mut inside = false;
+  let mut j = n-1;
+  for i in 0..n {
+    let (xi, yi, xj, yj) = (ring[i*2], ring[i*2+1], ring[j*2], ring[j*2+1]);
+    if (yi>py) != (yj>py) && px < (xj-xi)*(py-yi)/(yj-yi)+xi {
+      inside = !inside;
+    }
+    j = i;
+  }
+  inside
+}
+
+
+// ----- louvre's cycles ----- //
+
+/// Resolves rings (with dim 2, each in its own winding) into simple rings bounding the region whose winding number is `filled`.
+/// Outer rings are CCW and holes are CW. Rings don't cross or touch each other;
+/// a ring passes through a point twice where its part of the region touches itself (e.g. a hole touching the outer ring).
+///
+/// The rings, chained by `link_rings`, are decomposed into cycles the same way as [`triangulate`] decomposes a polygon (see `decompose_rings`).
+/// The winding number of a region is the sum of the windings (+1 for CCW, -1 for CW) of the cycles around it;
+/// a cycle is on the boundary when the region just inside it and the one just outside it aren't both filled or both empty.
+pub(crate) fn resolve_winding(rings: &[Vec<f64>], filled: impl Fn(i32) -> bool) -> Vec<Vec<f64>> {
+  let (cycles, windings) = wound_cycles(rings);
+  boundary(&cycles, &windings, filled)
+}
+
+/// Triangulates the regions of rings (with dim 2, each in its own winding) whose winding number isn't 0,
+/// the regions of each winding number on their own. Returns the winding number of each triangle with the mesh.
+pub(crate) fn triangulate_windings(rings: &[Vec<f64>], backend: Backend) -> (Vec<f64>, Vec<usize>, Vec<i32>) {
+  let (cycles, windings) = wound_cycles(rings);
+  let mut levels: Vec<i32> = windings.iter().flatten().flat_map(|&(outside, inside)| [outside, inside]).filter(|&w| w!=0).collect();
+  levels.sort_unstable();
+  levels.dedup();
+
+  let mut new_data: Vec<f64> = Vec::new();
+  let mut indices: Vec<usize> = Vec::new();
+  let mut numbers: Vec<i32> = Vec::new();
+  for &level in levels.iter() {
+    for polygon in group_polygons(boundary(&cycles, &windings, |w| w==level)).iter() {
+      append_mesh(triangulate_resolved_with(polygon, backend), &mut new_data, &mut indices);
+    }
+    numbers.resize(indices.len()/3, level);
+  }
+  (new_data, indices, numbers)
+}
+
+/// The cycles of rings (see [`resolve_winding`]) with the winding numbers just outside and just inside each one;
+/// `None` for a cycle of horizontal edges only.
+fn wound_cycles(rings: &[Vec<f64>]) -> (Vec<ResolvedRing>, Vec<Option<(i32, i32)>>) {
+  let mut cycles: Vec<ResolvedRing> = Vec::new();
+  for cycle in decompose_rings(&link_rings(rings)).iter() {
+    split_pinches(cycle, &mut cycles);
+  }
+  let signs: Vec<i32> = cycles.iter().map(|c| if c.winding==Winding::CW { -1 } else { 1 }).collect();
+
+  // The winding number just outside a cycle is found by one sweep, left of the midpoints of its edges:
+  // the cycles are CCW, so the inside is left of an edge going up, and right of one going down.
+  // The tallest edge which no other edge runs over is used, as the order of flat edges is least sure.
+  let mut segments: Vec<Segment> = Vec::new();
+  let mut queries: Vec<Query> = Vec::new();
+  let mut first: Vec<usize> = Vec::with_capacity(cycles.len()+1);
+  for (i, cycle) in cycles.iter().enumerate() {
+    let n = cycle.data.len()/2;
+    first.push(segments.len());
+    segments.extend((0..n).map(|k| {
+      let (a, b) = (k*2, (k+1)%n*2);
+      (cycle.data[a], cycle.data[a+1], cycle.data[b], cycle.data[b+1], signs[i])
+    }));
+  }
+  first.push(segments.len());
+  queries.extend((0..segments.len()).map(Query::LeftOf));
+  let numbers = winding_numbers(&segments, &queries);
+  let height = |e: usize| (segments[e].3-segments[e].1).abs();
+  let outer: Vec<Option<i32>> = (0..cycles.len()).map(|i| {
+    (first[i]..first[i+1]).filter(|&e| height(e)>0.)
+      .filter_map(|e| numbers[e].map(|left| (e, if segments[e].1<segments[e].3 { left-signs[i] } else { left })))
+      .max_by(|p, q| height(p.0).total_cmp(&height(q.0)))
+      .map(|(_, outside)| outside)
+  }).collect();
+
+  // Where all the edges of a cycle run along other cycles, the sweep can't tell: then it is inside a cycle
+  // if it is inside the cycle's outline, and a cycle on top of another one is inside the larger one, or the first one of the same area.
+  let areas: Vec<f64> = cycles.iter().map(|c| ring_area(&c.data)).collect();
+  let bboxes: Vec<[f64; 4]> = cycles.iter().map(|c| {
+    c.data.chunks_exact(2).fold([f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY], |b, p| {
+      [b[0].min(p[0]), b[1].min(p[1]), b[2].max(p[0]), b[3].max(p[1])]
+    })
+  }).collect();
+  let around = |j: usize, i: usize| {
+    let (a, b) = (bboxes[j], bboxes[i]);
+    j!=i && a[0]<=b[0] && a[1]<=b[1] && a[2]>=b[2] && a[3]>=b[3]
+      && encloses(&cycles[j].data, &cycles[i].data).unwrap_or(areas[j]>areas[i] || (areas[j]==areas[i] && j<i))
+  };
+
+  let windings: Vec<Option<(i32, i32)>> = (0..cycles.len()).map(|i| {
+    if (first[i]..first[i+1]).all(|e| height(e)==0.) {
+      return None;
+    }
+    let outside = outer[i].unwrap_or_else(|| (0..cycles.len()).filter(|&j| around(j, i)).map(|j| signs[j]).sum());
+    Some((outside, outside+signs[i]))
+  }).collect();
+  (cycles, windings)
+}
+
+/// Boundary of the region whose winding number is `filled`, as simple rings (see [`resolve_winding`]).
+fn boundary(cycles: &[ResolvedRing], windings: &[Option<(i32, i32)>], filled: impl Fn(i32) -> bool) -> Vec<Vec<f64>> {
+  let mut boundary: Vec<Vec<f64>> = Vec::new();
+  for (cycle, &winding) in cycles.iter().zip(windings.iter()) {
+    let Some((outside, inside)) = winding else {
+      continue;
+    };
+    match (filled(outside), filled(inside)) {
+      (false, true) => boundary.push(cycle.data.clone()),
+      (true, false) => boundary.push(cycle.data.chunks_exact(2).rev().flatten().copied().collect()),
+      _ => {},
+    }
+  }
+  link_rings(&boundary).into_iter().filter(|r| ring_area(r)!=0.).collect()
+}
+
+/// Chains the edges of rings (with dim 2) again, into rings which don't cross each other where they meet at a point.
+/// Edges are split at the points of other rings lying on them, and edges running both ways cancel out,
+/// so the winding numbers around any point stay the same.
+///
+/// Around a point, the edges going in and out are paired like parentheses, each going in with the first one out clockwise from it.
+/// Then rings bounding a region on their left bound one connected part of it each:
+/// rings touching at a point are split there, and a hole touching its outer ring is joined to it at the point.
+fn link_rings(rings: &[Vec<f64>]) -> Vec<Vec<f64>> {
+  let key = |x: f64, y: f64| ((x+0.).to_bits(), (y+0.).to_bits());
+  let mut nodes: Vec<(f64, f64)> = Vec::new();
+  let mut ids: HashMap<(u64, u64), usize> = HashMap::new();
+  let mut segments: Vec<(usize, usize)> = Vec::new();
+  for ring in rings.iter() {
+    let mut points: Vec<usize> = Vec::new();
+    for p in ring.chunks_exact(2) {
+      let id = *ids.entry(key(p[0], p[1])).or_insert_with(|| { nodes.push((p[0], p[1])); nodes.len()-1 });
+      if points.last()!=Some(&id) {
+        points.push(id);
+      }
+    }
+    while points.len()>1 && points.first()==points.last() {
+      points.pop();
+    }
+    if points.len()>=2 {
+      segments.extend((0..points.len()).map(|k| (points[k], points[(k+1)%points.len()])));
+    }
+  }
+
+  // The decomposition can't order edges running the same way on top of each other (lanes) where another edge crosses them,
+  // nor more than two edges crossing at a point, so those crossing points are added in a second round:
+  // the edges meet there at a point, and are paired below.
+  let mut edges: Vec<(usize, usize)> = Vec::new();
+  let mut alive: Vec<bool> = Vec::new();
+  for round in 0..2 {
+    // points within rounding of each other (e.g. a corner computed twice) are one point
+    let mut by_x: Vec<usize> = (0..nodes.len()).collect();
+    by_x.sort_by(|&i, &j| nodes[i].0.partial_cmp(&nodes[j].0).unwrap_or(Ordering::Equal));
+    let mut same: Vec<usize> = (0..nodes.len()).collect();
+    for k in 0..by_x.len() {
+      let (x, y) = nodes[by_x[k]];
+      let tolerance = x.abs().max(y.abs())*1e-12;
+      if let Some(&m) = by_x[..k].iter().rev().take_while(|&&m| x-nodes[m].0<=tolerance).find(|&&m| (nodes[m].1-y).abs()<=tolerance) {
+        same[by_x[k]] = same[m];
+      }
+    }
+    by_x.retain(|&n| same[n]==n);
+
+    // split the segments at the points on them
+    edges.clear();
+    for &(a, b) in segments.iter() {
+      let (a, b) = (same[a], same[b]);
+      if a==b {
+        continue;
+      }
+      let ((ax, ay), (bx, by)) = (nodes[a], nodes[b]);
+      let (dx, dy) = (bx-ax, by-ay);
+      let len2 = dx*dx + dy*dy;
+      // points off the segment by rounding count as on it, also past the x range of an upright one
+      let slack = len2.sqrt()*1e-12;
+      let from = by_x.partition_point(|&n| nodes[n].0<ax.min(bx)-slack);
+      let mut on: Vec<(f64, usize)> = by_x[from..].iter()
+        .take_while(|&&n| nodes[n].0<=ax.max(bx)+slack)
+        .filter(|&&n| n!=a && n!=b)
+        .filter_map(|&n| {
+          let (px, py) = nodes[n];
+          let t = ((px-ax)*dx + (py-ay)*dy)/len2;
+          let cross = dx*(py-ay) - dy*(px-ax);
+          (t>0. && t<1. && cross.abs()<=len2*1e-12).then_some((t, n))
+        })
+        .collect();
+      on.sort_by(|p, q| p.0.partial_cmp(&q.0).unwrap_or(Ordering::Equal));
+      let mut last = a;
+      for (_, n) in on.into_iter().chain([(1., b)]) {
+        edges.push((last, n));
+        last = n;
+      }
+    }
+
+    alive = vec![true; edges.len()];
+    let mut open: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for (e, &(a, b)) in edges.iter().enumerate() {
+      match open.get_mut(&(b, a)).and_then(|v| v.pop()) {
+        Some(f) => { alive[e] = false; alive[f] = false; },
+        None => open.entry((a, b)).or_default().push(e),
+      }
+    }
+    if round>0 {
+      break;
+    }
+
+    // crossings of the remaining segments, which are sorted by their left end to find them.
+    let mut open_segments: Vec<(usize, usize, bool)> = open.iter().filter(|(_, v)| !v.is_empty()).map(|(&(a, b), v)| (a, b, v.len()>1)).collect();
+    let left = |s: &(usize, usize, bool)| nodes[s.0].0.min(nodes[s.1].0);
+    open_segments.sort_by(|p, q| left(p).partial_cmp(&left(q)).unwrap_or(Ordering::Equal));
+    let mut crossings: Vec<(f64, f64)> = Vec::new();
+    let mut meets: Vec<(f64, f64, usize, usize)> = Vec::new();
+    for (i, &(a, b, lane_ab)) in open_segments.iter().enumerate() {
+      let ((ax, ay), (bx, by)) = (nodes[a], nodes[b]);
+      for (j, &(c, d, lane_cd)) in open_segments.iter().enumerate().skip(i+1) {
+        if left(&open_segments[j])>ax.max(bx) {
+          break;
+        }
+        if c==a || c==b || d==a || d==b {
+          continue;
+        }
+        let ((cx, cy), (dx, dy)) = (nodes[c], nodes[d]);
+        if cy.min(dy)>ay.max(by) || cy.max(dy)<ay.min(by) {
+          continue;
+        }
+        let den = (bx-ax)*(dy-cy) - (by-ay)*(dx-cx);
+        if den==0. {
+          continue;
+        }
+        let t = ((cx-ax)*(dy-cy) - (cy-ay)*(dx-cx))/den;
+        let u = ((cx-ax)*(by-ay) - (cy-ay)*(bx-ax))/den;
+        if t>0. && t<1. && u>0. && u<1. {
+          let (x, y) = (ax + t*(bx-ax), ay + t*(by-ay));
+          if lane_ab || lane_cd {
+            crossings.push((x, y));
+          } else {
+            meets.push((x, y, i, j));
+          }
+        }
+      }
+    }
+    // The decomposition doesn't take more than two segments crossing at a point either.
+    meets.sort_by(|p, q| p.0.partial_cmp(&q.0).unwrap_or(Ordering::Equal));
+    let mut grouped = vec![false; meets.len()];
+    for k in 0..meets.len() {
+      if grouped[k] {
+        continue;
+      }
+      let (x, y, _, _) = meets[k];
+      let tolerance = x.abs().max(y.abs())*1e-10;
+      let mut segments_at: Vec<usize> = Vec::new();
+      for m in k..meets.len() {
+        if meets[m].0-x>tolerance {
+          break;
+        }
+        if (meets[m].1-y).abs()<=tolerance {
+          grouped[m] = true;
+          segments_at.extend([meets[m].2, meets[m].3]);
+        }
+      }
+      segments_at.sort_unstable();
+      segments_at.dedup();
+      if segments_at.len()>2 {
+        crossings.push((x, y));
+      }
+    }
+    if crossings.is_empty() {
+      break;
+    }
+    nodes.extend(crossings);
+  }
+
+  // Pair the edges around each point, going clockwise twice around it.
+  // Edges running the same way are taken as lanes side by side, each one on the right of the ones before it.
+  let angle = |a: usize, b: usize| (nodes[b].1-nodes[a].1).atan2(nodes[b].0-nodes[a].0);
+  let mut lanes: HashMap<(usize, usize), i64> = HashMap::new();
+  let mut around: Vec<Vec<(f64, i64, bool, usize)>> = vec![Vec::new(); nodes.len()];
+  for (e, &(a, b)) in edges.iter().enumerate() {
+    if alive[e] {
+      let lane = lanes.entry((a, b)).or_insert(0);
+      around[a].push((angle(a, b), -*lane, true, e));
+      around[b].push((angle(b, a), *lane, false, e));
+      *lane += 1;
+    }
+  }
+  let mut next: Vec<usize> = vec![usize::MAX; edges.len()];
+  let mut taken = vec![false; edges.len()];
+  for ends in around.iter_mut() {
+    ends.sort_by(|p, q| q.0.partial_cmp(&p.0).unwrap_or(Ordering::Equal).then(q.1.cmp(&p.1)));
+    let mut stack: Vec<usize> = Vec::new();
+    for k in 0..ends.len()*2 {
+      let (_, _, out, e) = ends[k%ends.len()];
+      if !out {
+        if k<ends.len() {
+          stack.push(e);
+        }
+      } else if !taken[e] {
+        if let Some(f) = stack.pop() {
+          next[f] = e;
+          taken[e] = true;
+        }
+      }
+    }
+  }
+
+  let mut used = vec![false; edges.len()];
+  let mut result: Vec<Vec<f64>> = Vec::new();
+  for start in 0..edges.len() {
+    if !alive[start] || used[start] {
+      continue;
+    }
+    let mut ring: Vec<usize> = Vec::new();
+    let mut e = start;
+    while !used[e] {
+      used[e] = true;
+      ring.push(edges[e].0);
+      e = next[e];
+      if e==usize::MAX {
+        break;
+      }
+    }
+    // start from a point the ring passes once, which ear clipping takes better than a pinch.
+    let first = (0..ring.len()).find(|&k| ring.iter().filter(|&&n| n==ring[k]).count()==1).unwrap_or(0);
+    ring.rotate_left(first);
+    if ring.len()>=3 {
+      result.push(ring.iter().flat_map(|&n| [nodes[n].0, nodes[n].1]).collect());
+    }
+  }
+  result
+}
+
+/// Splits a cycle which passes through a point more than once (where it was traced through several crossings at the point)
+/// into loops, each made CCW with the winding it goes around in.
+fn split_pinches(cycle: &ResolvedRing, loops: &mut Vec<ResolvedRing>) {
+  let mut push = |points: &[(f64, f64)]| {
+    let data: Vec<f64> = points.iter().flat_map(|&(x, y)| [x, y]).collect();
+    let a = ring_area(&data);
+    if points.len()<3 || a==0. {
+      return;
+    }
+    let winding = if (a>0.) == (cycle.winding!=Winding::CW) { Winding::CCW } else { Winding::CW };
+    let data = if a>0. { data } else { data.chunks_exact(2).rev().flatten().copied().collect() };
+    loops.push(ResolvedRing { data, winding });
+  };
+
+  let mut path: Vec<(f64, f64)> = Vec::new();
+  let mut at: HashMap<(u64, u64), usize> = HashMap::new();
+  for p in cycle.data.chunks_exact(2) {
+    let p = (p[0], p[1]);
+    let key = ((p.0+0.).to_bits(), (p.1+0.).to_bits());
+    match at.get(&key) {
+      Some(&k) => {
+        push(&path[k..]);
+        for q in path.drain(k+1..) {
+          at.remove(&((q.0+0.).to_bits(), (q.1+0.).to_bits()));
+        }
+      },
+      None => {
+        at.insert(key, path.len());
+        path.push(p);
+      },
+    }
+  }
+  push(&path);
+}
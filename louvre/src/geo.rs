@@ -0,0 +1,64 @@
+//! `geo-types` interop
+//!
+//! [`Triangulate`] is implemented for `Polygon`, `MultiPolygon` and `LineString` of `geo_types`.
+//! The closing coordinate of `geo_types` rings is handled here, so there is no need to strip it by hand.
+
+use crate::*;
+use crate::polygon::append_mesh;
+use geo_types::{Coord, LineString, MultiPolygon, Polygon, Triangle};
+
+
+/// Triangulation of `geo_types` geometries.
+pub trait Triangulate {
+  /// Returns the mesh buffers: a new coordinates array (dim 2) and a index array of it.
+  fn louvre_mesh(&self) -> (Vec<f64>, Vec<usize>);
+
+  /// Returns the triangles of the mesh.
+  fn louvre_triangles(&self) -> Vec<Triangle<f64>> {
+    let (new_data, indices) = self.louvre_mesh();
+    mesh_triangles(&new_data, &indices)
+  }
+}
+
+impl Triangulate for LineString<f64> {
+  /// The line string is taken as a (closed or not) ring.
+  fn louvre_mesh(&self) -> (Vec<f64>, Vec<usize>) {
+    let mut data = line_string_data(self);
+    if data.len()<6 {
+      return (Vec::new(), Vec::new());
+    }
+    triangulate(&mut data, 2)
+  }
+}
+
+impl Triangulate for Polygon<f64> {
+  fn louvre_mesh(&self) -> (Vec<f64>, Vec<usize>) {
+    let mut rings: Vec<Vec<f64>> = vec![line_string_data(self.exterior())];
+    rings.extend(self.interiors().iter().map(line_string_data));
+    triangulate_polygon(&rings, 2)
+  }
+}
+
+impl Triangulate for MultiPolygon<f64> {
+  fn louvre_mesh(&self) -> (Vec<f64>, Vec<usize>) {
+    let mut new_data: Vec<f64> = Vec::new();
+    let mut indices: Vec<usize> = Vec::new();
+    for polygon in self.iter() {
+      append_mesh(polygon.louvre_mesh(), &mut new_data, &mut indices);
+    }
+    (new_data, indices)
+  }
+}
+
+/// Flat (x, y) coordinates array of a line string.
+pub fn line_string_data(line_string: &LineString<f64>) -> Vec<f64> {
+  line_string.coords().flat_map(|c| [c.x, c.y]).collect()
+}
+
+/// Makes triangles out of mesh buffers (with dim 2).
+pub fn mesh_triangles(new_data: &[f64], indices: &[usize]) -> Vec<Triangle<f64>> {
+  let coord = |i: usize| Coord{ x: new_data[i*2], y: new_data[i*2+1] };
+  indices.chunks_exact(3)
+    .map(|t| Triangle::new(coord(t[0]), coord(t[1]), coord(t[2])))
+    .collect()
+}
@@ -0,0 +1,166 @@
+//! GeoJSON input
+//!
+//! Triangulates every Polygon and MultiPolygon of a GeoJSON object (Geometry, Feature or FeatureCollection).
+//! Interior rings are handled by [`triangulate_polygon`].
+
+use crate::*;
+use crate::polygon::append_mesh;
+use serde_json::{Map, Value};
+use std::fmt;
+
+
+/// Triangulation result of a single GeoJSON feature.
+/// All polygons of the feature are merged into one mesh.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureMesh {
+  /// Index of the feature in its FeatureCollection (0 for a single Feature or Geometry).
+  pub index: usize,
+  /// `id` member of the feature, if any.
+  pub id: Option<Value>,
+  /// `properties` member of the feature, if any.
+  pub properties: Option<Map<String, Value>>,
+  pub new_data: Vec<f64>,
+  pub indices: Vec<usize>,
+}
+
+#[derive(Debug)]
+pub enum GeoJsonError {
+  Json(serde_json::Error),
+  /// Unknown or missing `type` member.
+  InvalidType(String),
+  /// `coordinates` member is missing or malformed.
+  InvalidCoordinates,
+}
+
+impl fmt::Display for GeoJsonError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      GeoJsonError::Json(e) => write!(f, "invalid json: {}", e),
+      GeoJsonError::InvalidType(t) => write!(f, "invalid geojson type: {}", t),
+      GeoJsonError::InvalidCoordinates => write!(f, "invalid geojson coordinates"),
+    }
+  }
+}
+
+impl std::error::Error for GeoJsonError {}
+
+impl From<serde_json::Error> for GeoJsonError {
+  fn from(e: serde_json::Error) -> Self {
+    GeoJsonError::Json(e)
+  }
+}
+
+
+/// Parses a GeoJSON text and triangulates it. See [`triangulate_geojson`].
+pub fn triangulate_geojson_str(s: &str) -> Result<Vec<FeatureMesh>, GeoJsonError> {
+  let value: Value = serde_json::from_str(s)?;
+  triangulate_geojson(&value)
+}
+
+/// Triangulates every Polygon/MultiPolygon of a GeoJSON Geometry, Feature or FeatureCollection.
+///
+/// Returns one [`FeatureMesh`] per feature which has polygonal geometry, keyed by the feature's index.
+/// Features without any polygon (Point, LineString, null geometry...) are skipped.
+pub fn triangulate_geojson(geojson: &Value) -> Result<Vec<FeatureMesh>, GeoJsonError> {
+  let mut meshes: Vec<FeatureMesh> = Vec::new();
+  match type_of(geojson)? {
+    "FeatureCollection" => {
+      let features = geojson.get("features").and_then(|x| x.as_array())
+        .ok_or(GeoJsonError::InvalidType("FeatureCollection without features".to_string()))?;
+      for (index, feature) in features.iter().enumerate() {
+        if let Some(mesh) = feature_mesh(index, feature)? {
+          meshes.push(mesh);
+        }
+      }
+    },
+    "Feature" => {
+      if let Some(mesh) = feature_mesh(0, geojson)? {
+        meshes.push(mesh);
+      }
+    },
+    _ => {
+      let (new_data, indices) = triangulate_geometry(geojson)?;
+      if !indices.is_empty() {
+        meshes.push(FeatureMesh{ index: 0, id: None, properties: None, new_data, indices });
+      }
+    },
+  }
+  Ok(meshes)
+}
+
+/// Triangulates a GeoJSON Geometry object and returns a merged mesh of all its polygons.
+/// Non-polygonal geometries give an empty mesh.
+pub fn triangulate_geometry(geometry: &Value) -> Result<(Vec<f64>, Vec<usize>), GeoJsonError> {
+  let mut new_data: Vec<f64> = Vec::new();
+  let mut indices: Vec<usize> = Vec::new();
+  append_geometry(geometry, &mut new_data, &mut indices)?;
+  Ok((new_data, indices))
+}
+
+fn type_of(value: &Value) -> Result<&str, GeoJsonError> {
+  value.get("type").and_then(|x| x.as_str())
+    .ok_or(GeoJsonError::InvalidType("missing type".to_string()))
+}
+
+fn feature_mesh(index: usize, feature: &Value) -> Result<Option<FeatureMesh>, GeoJsonError> {
+  let t = type_of(feature)?;
+  if t!="Feature" {
+    return Err(GeoJsonError::InvalidType(t.to_string()));
+  }
+  let (new_data, indices) = match feature.get("geometry") {
+    None | Some(Value::Null) => return Ok(None),
+    Some(geometry) => triangulate_geometry(geometry)?,
+  };
+  if indices.is_empty() {
+    return Ok(None);
+  }
+  Ok(Some(FeatureMesh {
+    index,
+    id: feature.get("id").cloned(),
+    properties: feature.get("properties").and_then(|x| x.as_object()).cloned(),
+    new_data,
+    indices,
+  }))
+}
+
+fn append_geometry(geometry: &Value, new_data: &mut Vec<f64>, indices: &mut Vec<usize>) -> Result<(), GeoJsonError> {
+  match type_of(geometry)? {
+    "Polygon" => {
+      let coords = geometry.get("coordinates").ok_or(GeoJsonError::InvalidCoordinates)?;
+      append_mesh(triangulate_polygon(&polygon_rings(coords)?, 2), new_data, indices);
+    },
+    "MultiPolygon" => {
+      let coords = geometry.get("coordinates").and_then(|x| x.as_array()).ok_or(GeoJsonError::InvalidCoordinates)?;
+      for polygon in coords.iter() {
+        append_mesh(triangulate_polygon(&polygon_rings(polygon)?, 2), new_data, indices);
+      }
+    },
+    "GeometryCollection" => {
+      let geometries = geometry.get("geometries").and_then(|x| x.as_array()).ok_or(GeoJsonError::InvalidCoordinates)?;
+      for g in geometries.iter() {
+        append_geometry(g, new_data, indices)?;
+      }
+    },
+    "Point" | "MultiPoint" | "LineString" | "MultiLineString" => {},
+    t => return Err(GeoJsonError::InvalidType(t.to_string())),
+  }
+  Ok(())
+}
+
+/// `[[[x, y], ...], ...]` => rings of flat (x, y) coordinates. Extra dimensions (altitude) are dropped.
+fn polygon_rings(coords: &Value) -> Result<Vec<Vec<f64>>, GeoJsonError> {
+  let rings = coords.as_array().ok_or(GeoJsonError::InvalidCoordinates)?;
+  rings.iter().map(|ring| {
+    let positions = ring.as_array().ok_or(GeoJsonError::InvalidCoordinates)?;
+    let mut data: Vec<f64> = Vec::with_capacity(positions.len()*2);
+    for p in positions.iter() {
+      let p = p.as_array().ok_or(GeoJsonError::InvalidCoordinates)?;
+      if p.len()<2 {
+        return Err(GeoJsonError::InvalidCoordinates);
+      }
+      data.push(p[0].as_f64().ok_or(GeoJsonError::InvalidCoordinates)?);
+      data.push(p[1].as_f64().ok_or(GeoJsonError::InvalidCoordinates)?);
+    }
+    Ok(data)
+  }).collect()
+}
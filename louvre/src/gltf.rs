@@ -0,0 +1,198 @@
+//! glTF 2.0 export
+//!
+//! Packs one or more triangulations into a glTF asset, either as `.gltf` (json) + `.bin` or as a single binary `.glb`.
+//! Each [`GltfMesh`] becomes a mesh with one triangle primitive, and a node of the default scene.
+//!
+//! * POSITION is a `FLOAT` `VEC3` accessor (with min/max, as the spec requires).
+//!   Coordinates with `dim` 2 get z=0; with `dim` 3 or more, the third component is taken as z.
+//! * The index accessor uses the smallest of `UNSIGNED_BYTE`, `UNSIGNED_SHORT` and `UNSIGNED_INT` which fits the mesh.
+//!
+//! Triangles of [`triangulate`](crate::triangulate) are counter-clockwise on the xy plane, so they face +z (glTF's front face).
+
+use serde_json::{json, Value};
+use std::io;
+use std::path::Path;
+
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const FLOAT: u32 = 5126;
+const UNSIGNED_BYTE: u32 = 5121;
+const UNSIGNED_SHORT: u32 = 5123;
+const UNSIGNED_INT: u32 = 5125;
+const TRIANGLES: u32 = 4;
+
+
+/// A triangulation to be written; `new_data` with `dim`, and `indices` of it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GltfMesh<'a> {
+  pub new_data: &'a [f64],
+  pub dim: usize,
+  pub indices: &'a [usize],
+  /// Name of the mesh and its node.
+  pub name: Option<&'a str>,
+}
+
+impl<'a> GltfMesh<'a> {
+  pub fn new(new_data: &'a [f64], dim: usize, indices: &'a [usize]) -> Self {
+    GltfMesh { new_data, dim, indices, name: None }
+  }
+
+  pub fn with_name(self, name: &'a str) -> Self {
+    GltfMesh { name: Some(name), ..self }
+  }
+}
+
+
+/// Builds a glTF json document and its binary buffer.
+/// `bin_uri` is the path of the buffer relative to the json, e.g. `"mesh.bin"`, written as a uri (percent-encoded);
+/// `None` leaves it out, as for `.glb`.
+///
+/// Meshes without any triangle are skipped, since glTF doesn't allow empty accessors.
+/// Without any mesh left, the document has no scene, node or mesh either.
+pub fn to_gltf(meshes: &[GltfMesh], bin_uri: Option<&str>) -> (String, Vec<u8>) {
+  let mut bin: Vec<u8> = Vec::new();
+  let mut buffer_views: Vec<Value> = Vec::new();
+  let mut accessors: Vec<Value> = Vec::new();
+  let mut gltf_meshes: Vec<Value> = Vec::new();
+  let mut nodes: Vec<Value> = Vec::new();
+
+  for mesh in meshes.iter().filter(|m| m.indices.len()>=3) {
+    let dim = mesh.dim;
+    let count = mesh.new_data.len()/dim;
+
+    // ----- positions ----- //
+    let mut min = [f32::INFINITY; 3];
+    let mut max = [f32::NEG_INFINITY; 3];
+    let offset = bin.len();
+    for i in 0..count {
+      let z = if dim>=3 { mesh.new_data[i*dim+2] } else { 0. };
+      for (k, x) in [mesh.new_data[i*dim], mesh.new_data[i*dim+1], z].into_iter().enumerate() {
+        let x = x as f32;
+        min[k] = min[k].min(x);
+        max[k] = max[k].max(x);
+        bin.extend(x.to_le_bytes());
+      }
+    }
+    buffer_views.push(json!({ "buffer": 0, "byteOffset": offset, "byteLength": bin.len()-offset, "target": ARRAY_BUFFER }));
+    accessors.push(json!({
+      "bufferView": buffer_views.len()-1, "componentType": FLOAT, "count": count, "type": "VEC3",
+      "min": min, "max": max,
+    }));
+    let position = accessors.len()-1;
+
+    // ----- indices ----- //
+    // the maximum value of each type is reserved for primitive restart, and not a valid index.
+    let max_index = mesh.indices.iter().copied().max().unwrap_or(0);
+    let component_type = if max_index<u8::MAX as usize {
+      UNSIGNED_BYTE
+    } else if max_index<u16::MAX as usize {
+      UNSIGNED_SHORT
+    } else {
+      UNSIGNED_INT
+    };
+    let offset = bin.len();
+    for &i in mesh.indices.iter() {
+      match component_type {
+        UNSIGNED_BYTE => bin.push(i as u8),
+        UNSIGNED_SHORT => bin.extend((i as u16).to_le_bytes()),
+        _ => bin.extend((i as u32).to_le_bytes()),
+      }
+    }
+    buffer_views.push(json!({ "buffer": 0, "byteOffset": offset, "byteLength": bin.len()-offset, "target": ELEMENT_ARRAY_BUFFER }));
+    accessors.push(json!({
+      "bufferView": buffer_views.len()-1, "componentType": component_type, "count": mesh.indices.len(), "type": "SCALAR",
+    }));
+    // next positions must be 4-byte aligned
+    while bin.len()%4!=0 {
+      bin.push(0);
+    }
+
+    let mut gltf_mesh = json!({
+      "primitives": [{ "attributes": { "POSITION": position }, "indices": accessors.len()-1, "mode": TRIANGLES }],
+    });
+    let mut node = json!({ "mesh": gltf_meshes.len() });
+    if let Some(name) = mesh.name {
+      gltf_mesh["name"] = json!(name);
+      node["name"] = json!(name);
+    }
+    gltf_meshes.push(gltf_mesh);
+    nodes.push(node);
+  }
+
+  let mut buffer = json!({ "byteLength": bin.len() });
+  if let Some(uri) = bin_uri {
+    buffer["uri"] = json!(escape_uri(uri));
+  }
+  let mut root = json!({
+    "asset": { "version": "2.0", "generator": concat!("louvre ", env!("CARGO_PKG_VERSION")) },
+  });
+  // glTF doesn't allow empty arrays
+  if !nodes.is_empty() {
+    root["scene"] = json!(0);
+    root["scenes"] = json!([{ "nodes": (0..nodes.len()).collect::<Vec<usize>>() }]);
+    root["nodes"] = json!(nodes);
+    root["meshes"] = json!(gltf_meshes);
+  }
+  if !bin.is_empty() {
+    root["buffers"] = json!([buffer]);
+    root["bufferViews"] = json!(buffer_views);
+    root["accessors"] = json!(accessors);
+  }
+  (root.to_string(), bin)
+}
+
+/// Percent-encodes a relative path for a uri, keeping unreserved characters and `/`.
+fn escape_uri(path: &str) -> String {
+  let mut uri = String::with_capacity(path.len());
+  for b in path.bytes() {
+    if b.is_ascii_alphanumeric() || b"-._~/".contains(&b) {
+      uri.push(b as char);
+    } else {
+      uri.push_str(&format!("%{:02X}", b));
+    }
+  }
+  uri
+}
+
+/// Builds a binary glTF (`.glb`) of the meshes.
+pub fn to_glb(meshes: &[GltfMesh]) -> Vec<u8> {
+  let (json, mut bin) = to_gltf(meshes, None);
+  let mut json = json.into_bytes();
+  while json.len()%4!=0 {
+    json.push(b' ');
+  }
+  while bin.len()%4!=0 {
+    bin.push(0);
+  }
+
+  let length = 12 + 8+json.len() + if bin.is_empty() { 0 } else { 8+bin.len() };
+  let mut glb: Vec<u8> = Vec::with_capacity(length);
+  glb.extend(b"glTF");
+  glb.extend(2u32.to_le_bytes());
+  glb.extend((length as u32).to_le_bytes());
+  glb.extend((json.len() as u32).to_le_bytes());
+  glb.extend(b"JSON");
+  glb.extend(json);
+  if !bin.is_empty() {
+    glb.extend((bin.len() as u32).to_le_bytes());
+    glb.extend(b"BIN\0");
+    glb.extend(bin);
+  }
+  glb
+}
+
+/// Writes `path` (`.gltf`) and its buffer next to it, with the same file stem and the `.bin` extension.
+pub fn write_gltf<P: AsRef<Path>>(path: P, meshes: &[GltfMesh]) -> io::Result<()> {
+  let path = path.as_ref();
+  let bin_path = path.with_extension("bin");
+  let bin_uri = bin_path.file_name().and_then(|x| x.to_str())
+    .ok_or(io::Error::new(io::ErrorKind::InvalidInput, "invalid gltf path"))?;
+  let (json, bin) = to_gltf(meshes, Some(bin_uri));
+  std::fs::write(path, json)?;
+  std::fs::write(&bin_path, bin)
+}
+
+/// Writes a binary glTF (`.glb`) file.
+pub fn write_glb<P: AsRef<Path>>(path: P, meshes: &[GltfMesh]) -> io::Result<()> {
+  std::fs::write(path, to_glb(meshes))
+}
@@ -0,0 +1,202 @@
+//! Half-edge connectivity of a triangulation
+//!
+//! [`triangulate`] gives a flat index array, whose vertices are not shared between triangles everywhere
+//! (intersection points and the points of a decomposed polygon come out once per simple cycle).
+//! [`HalfEdgeMesh`] welds vertices with the same position and links each half-edge to its twin,
+//! for walking over neighbouring triangles, boundary loops and vertex one-rings.
+//!
+//! Half-edge `h` belongs to triangle `h/3`, and goes from its corner `h%3` to the next corner.
+
+use crate::*;
+use std::collections::HashMap;
+
+
+/// Triangle mesh with half-edge adjacency. Triangles are counter-clockwise, as louvre makes them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HalfEdgeMesh {
+  /// Welded coordinates array with dim 2.
+  pub positions: Vec<f64>,
+  /// Triangles over `positions`. Triangles whose corners weld together are dropped.
+  pub indices: Vec<usize>,
+  twins: Vec<Option<usize>>,
+  /// outgoing half-edges of each vertex, as ranges of `outgoing`
+  starts: Vec<usize>,
+  outgoing: Vec<usize>,
+}
+
+impl HalfEdgeMesh {
+  /// Builds the mesh from a coordinates array with dim 2 and a index array of it, like the output of [`triangulate`].
+  ///
+  /// An edge gets a twin when it is used exactly once in each direction; otherwise (at the boundary,
+  /// or where more than two triangles meet at an edge) its half-edges have none.
+  pub fn new(new_data: &[f64], indices: &[usize]) -> HalfEdgeMesh {
+    // ----- weld ----- //
+    let mut positions: Vec<f64> = Vec::new();
+    let mut merged: HashMap<[u64; 2], usize> = HashMap::new();
+    let mut welded: Vec<usize> = Vec::with_capacity(indices.len());
+    for t in indices.chunks_exact(3) {
+      let tri = [t[0], t[1], t[2]].map(|i| {
+        let p = [new_data[i*2], new_data[i*2+1]];
+        *merged.entry(p.map(|x| (x+0.).to_bits())).or_insert_with(|| {
+          positions.extend(p);
+          positions.len()/2-1
+        })
+      });
+      if tri[0]!=tri[1] && tri[1]!=tri[2] && tri[2]!=tri[0] {
+        welded.extend(tri);
+      }
+    }
+
+    // ----- twins ----- //
+    let mut edges: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+    for h in 0..welded.len() {
+      let e = edges.entry((welded[h], welded[next(h)])).or_insert((h, 0));
+      e.1 += 1;
+    }
+    let twins: Vec<Option<usize>> = (0..welded.len()).map(|h| {
+      let (a, b) = (welded[h], welded[next(h)]);
+      match (edges.get(&(a, b)), edges.get(&(b, a))) {
+        (Some(&(_, 1)), Some(&(g, 1))) => Some(g),
+        _ => None,
+      }
+    }).collect();
+
+    // ----- outgoing half-edges ----- //
+    // counting sort of half-edges by their origin
+    let n = positions.len()/2;
+    let mut starts: Vec<usize> = vec![0; n+1];
+    for &v in welded.iter() {
+      starts[v+1] += 1;
+    }
+    for k in 1..starts.len() {
+      starts[k] += starts[k-1];
+    }
+    let mut outgoing: Vec<usize> = vec![0; welded.len()];
+    let mut fill = starts.clone();
+    for (h, &v) in welded.iter().enumerate() {
+      outgoing[fill[v]] = h;
+      fill[v] += 1;
+    }
+
+    HalfEdgeMesh { positions, indices: welded, twins, starts, outgoing }
+  }
+
+  pub fn vertex_count(&self) -> usize {
+    self.positions.len()/2
+  }
+
+  pub fn triangle_count(&self) -> usize {
+    self.indices.len()/3
+  }
+
+  /// Next half-edge in the same triangle (counter-clockwise).
+  pub fn next(&self, h: usize) -> usize {
+    next(h)
+  }
+
+  /// Previous half-edge in the same triangle.
+  pub fn prev(&self, h: usize) -> usize {
+    h/3*3 + (h+2)%3
+  }
+
+  /// The opposite half-edge in the neighbouring triangle; `None` on the boundary.
+  pub fn twin(&self, h: usize) -> Option<usize> {
+    self.twins[h]
+  }
+
+  pub fn triangle(&self, h: usize) -> usize {
+    h/3
+  }
+
+  /// Vertex the half-edge starts from.
+  pub fn origin(&self, h: usize) -> usize {
+    self.indices[h]
+  }
+
+  /// Vertex the half-edge points to.
+  pub fn target(&self, h: usize) -> usize {
+    self.indices[next(h)]
+  }
+
+  /// Triangles sharing an edge with triangle t.
+  pub fn neighbours(&self, t: usize) -> impl Iterator<Item = usize> + '_ {
+    (t*3..t*3+3).filter_map(|h| self.twins[h].map(|g| g/3))
+  }
+
+  /// Half-edges without a twin.
+  pub fn boundary_edges(&self) -> impl Iterator<Item = usize> + '_ {
+    (0..self.twins.len()).filter(|&h| self.twins[h].is_none())
+  }
+
+  /// Half-edges starting from vertex v.
+  pub fn outgoing(&self, v: usize) -> &[usize] {
+    &self.outgoing[self.starts[v]..self.starts[v+1]]
+  }
+
+  /// Boundary loops as vertex indices: outer boundaries are counter-clockwise, and holes clockwise.
+  pub fn boundary_loops(&self) -> Vec<Vec<usize>> {
+    let mut visited: Vec<bool> = vec![false; self.twins.len()];
+    let mut loops: Vec<Vec<usize>> = Vec::new();
+    for start in self.boundary_edges() {
+      if visited[start] {
+        continue;
+      }
+      let mut ring: Vec<usize> = Vec::new();
+      let mut h = start;
+      while !visited[h] {
+        visited[h] = true;
+        ring.push(self.origin(h));
+        // turn clockwise around the target until the next boundary half-edge
+        h = next(h);
+        while let Some(g) = self.twins[h] {
+          h = next(g);
+        }
+      }
+      loops.push(ring);
+    }
+    loops
+  }
+
+  /// Neighbouring vertices of v, counter-clockwise around v.
+  /// Around a vertex where separate fans of triangles touch, the fans follow one another.
+  pub fn one_ring(&self, v: usize) -> Vec<usize> {
+    let outgoing = self.outgoing(v);
+    let mut seen: Vec<usize> = Vec::with_capacity(outgoing.len());
+    let mut ring: Vec<usize> = Vec::new();
+
+    // open fans first, from their clockwise-most edge; then closed fans.
+    let open = outgoing.iter().filter(|&&h| self.twins[h].is_none());
+    let closed = outgoing.iter().filter(|&&h| self.twins[h].is_some());
+    for &start in open.chain(closed) {
+      if seen.contains(&start) {
+        continue;
+      }
+      let mut h = start;
+      loop {
+        seen.push(h);
+        ring.push(self.target(h));
+        let p = self.prev(h);
+        match self.twins[p] {
+          Some(g) if g!=start => h = g,
+          Some(_) => break,
+          None => {
+            ring.push(self.origin(p));
+            break;
+          },
+        }
+      }
+    }
+    ring
+  }
+}
+
+
+/// Triangulates a polygon (see [`triangulate`]) into a [`HalfEdgeMesh`].
+pub fn triangulate_half_edges(data: &mut Vec<f64>, dim: usize) -> HalfEdgeMesh {
+  let (new_data, indices) = triangulate(data, dim);
+  HalfEdgeMesh::new(&new_data, &indices)
+}
+
+fn next(h: usize) -> usize {
+  h/3*3 + (h+1)%3
+}
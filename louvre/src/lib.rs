@@ -52,15 +52,72 @@ pub mod triangulate;
 pub use triangulate::*;
 pub use triangulate::triangulate;
 
+mod monotone;
+
+mod sweep;
+
 pub mod utils;
 use utils::*;
 
 pub mod structures;
 use structures::*;
 
+pub mod polygon;
+pub use polygon::triangulate_polygon;
+
+pub mod wkt;
+
+pub mod fill;
+pub use fill::FillRule;
+
+pub mod svg;
+
+pub mod render;
+
+pub mod export;
+
+pub mod extrude;
+
+pub mod planar;
+pub use planar::triangulate_3d;
+
+pub mod stroke;
+
+pub mod offset;
+
+pub mod boolean;
+
+pub mod measure;
+
+pub mod locate;
+
+pub mod halfedge;
+
+pub mod convex;
+
+pub mod strip;
+
+pub mod cache;
+
+pub mod uv;
+
+pub mod edit;
+
+pub mod canonical;
+pub use canonical::triangulate_canonical;
+
 use std::cmp::Ordering;
 use std::ptr;
 use std::f64;
 
 #[cfg(feature="html")]
-pub mod html;
\ No newline at end of file
+pub mod html;
+
+#[cfg(feature="geojson")]
+pub mod geojson;
+
+#[cfg(feature="geo-types")]
+pub mod geo;
+
+#[cfg(feature="gltf")]
+pub mod gltf;
\ No newline at end of file
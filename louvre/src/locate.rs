@@ -0,0 +1,153 @@
+//! Point location over a triangulation
+//!
+//! [`TriangleIndex`] buckets the triangles of a mesh into a uniform grid, so that
+//! "which triangle contains this point" only tests the few triangles of one cell.
+//! A hit comes with the barycentric coordinates of the point, for attribute interpolation.
+
+/// A triangle containing a query point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hit {
+  /// Index of the triangle, i.e. its position in `indices` divided by 3.
+  pub triangle: usize,
+  /// Barycentric coordinates of the point for the triangle's three vertices (in the order of `indices`).
+  pub barycentric: [f64; 3],
+}
+
+/// Uniform grid over the triangles of a mesh (`new_data` with dim 2, and `indices`).
+#[derive(Debug, Clone)]
+pub struct TriangleIndex {
+  new_data: Vec<f64>,
+  indices: Vec<usize>,
+  x0: f64,
+  y0: f64,
+  cell: f64,
+  cols: usize,
+  rows: usize,
+  /// triangles overlapping each cell, as ranges of `items`
+  starts: Vec<usize>,
+  items: Vec<usize>,
+}
+
+impl TriangleIndex {
+  /// Builds the index; each cell holds about one triangle on average.
+  pub fn new(new_data: &[f64], indices: &[usize]) -> TriangleIndex {
+    let n = indices.len()/3;
+    let (mut x0, mut y0, mut x1, mut y1) = (f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for &i in indices.iter() {
+      x0 = x0.min(new_data[i*2]);
+      y0 = y0.min(new_data[i*2+1]);
+      x1 = x1.max(new_data[i*2]);
+      y1 = y1.max(new_data[i*2+1]);
+    }
+    if n==0 {
+      (x0, y0, x1, y1) = (0., 0., 0., 0.);
+    }
+    let (w, h) = (x1-x0, y1-y0);
+    let cell = if w*h>0. { (w*h/n as f64).sqrt() } else { w.max(h).max(1.) };
+    // at most n cells a side, so a long thin extent doesn't make more than O(n) cells
+    let cell = cell.max(w.max(h)/n.max(1) as f64);
+    let cols = ((w/cell).ceil() as usize).max(1);
+    let rows = ((h/cell).ceil() as usize).max(1);
+
+    let mut index = TriangleIndex {
+      new_data: new_data.to_vec(), indices: indices[..n*3].to_vec(),
+      x0, y0, cell, cols, rows, starts: Vec::new(), items: Vec::new(),
+    };
+
+    // counting sort of (cell, triangle) pairs by cell
+    let mut counts: Vec<usize> = vec![0; cols*rows+1];
+    let mut pairs: Vec<(usize, usize)> = Vec::new();
+    for t in 0..n {
+      let (c0, r0, c1, r1) = index.cell_range(t);
+      for r in r0..=r1 {
+        for c in c0..=c1 {
+          pairs.push((r*cols+c, t));
+          counts[r*cols+c+1] += 1;
+        }
+      }
+    }
+    for k in 1..counts.len() {
+      counts[k] += counts[k-1];
+    }
+    let mut items: Vec<usize> = vec![0; pairs.len()];
+    let mut fill = counts.clone();
+    for (k, t) in pairs {
+      items[fill[k]] = t;
+      fill[k] += 1;
+    }
+    index.starts = counts;
+    index.items = items;
+    index
+  }
+
+  /// Number of triangles.
+  pub fn len(&self) -> usize {
+    self.indices.len()/3
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.indices.is_empty()
+  }
+
+  /// The triangle containing (x, y), if any. Points on a shared edge go to one of the triangles.
+  pub fn locate(&self, x: f64, y: f64) -> Option<Hit> {
+    let (c, r) = (self.col(x)?, self.row(y)?);
+    let k = r*self.cols+c;
+    self.items[self.starts[k]..self.starts[k+1]].iter()
+      .find_map(|&t| self.barycentric(t, x, y).map(|b| Hit{ triangle: t, barycentric: b }))
+  }
+
+  /// Locates many points given as a coordinates array with `dim`.
+  pub fn locate_all(&self, points: &[f64], dim: usize) -> Vec<Option<Hit>> {
+    points.chunks_exact(dim).map(|p| self.locate(p[0], p[1])).collect()
+  }
+
+  /// Interpolates a per-vertex attribute (`values`, `n` components per vertex of `new_data`) at (x, y).
+  pub fn interpolate(&self, x: f64, y: f64, values: &[f64], n: usize) -> Option<Vec<f64>> {
+    let hit = self.locate(x, y)?;
+    let t = &self.indices[hit.triangle*3..hit.triangle*3+3];
+    Some((0..n).map(|k| (0..3).map(|j| values[t[j]*n+k]*hit.barycentric[j]).sum()).collect())
+  }
+
+  fn col(&self, x: f64) -> Option<usize> {
+    let c = (x-self.x0)/self.cell;
+    if c>=0. && c<=self.cols as f64 { Some((c as usize).min(self.cols-1)) } else { None }
+  }
+
+  fn row(&self, y: f64) -> Option<usize> {
+    let r = (y-self.y0)/self.cell;
+    if r>=0. && r<=self.rows as f64 { Some((r as usize).min(self.rows-1)) } else { None }
+  }
+
+  /// Cells overlapped by the bbox of triangle t: (col0, row0, col1, row1).
+  fn cell_range(&self, t: usize) -> (usize, usize, usize, usize) {
+    let tri = &self.indices[t*3..t*3+3];
+    let xs = tri.iter().map(|&i| self.new_data[i*2]);
+    let ys = tri.iter().map(|&i| self.new_data[i*2+1]);
+    let (x0, x1) = xs.fold((f64::INFINITY, f64::NEG_INFINITY), |m, x| (m.0.min(x), m.1.max(x)));
+    let (y0, y1) = ys.fold((f64::INFINITY, f64::NEG_INFINITY), |m, y| (m.0.min(y), m.1.max(y)));
+    let c0 = self.col(x0).unwrap_or(0);
+    let r0 = self.row(y0).unwrap_or(0);
+    let c1 = self.col(x1).unwrap_or(self.cols-1);
+    let r1 = self.row(y1).unwrap_or(self.rows-1);
+    (c0, r0, c1, r1)
+  }
+
+  /// Barycentric coordinates of (x, y) in triangle t, if it is inside (or on the boundary).
+  fn barycentric(&self, t: usize, x: f64, y: f64) -> Option<[f64; 3]> {
+    let p = |j: usize| {
+      let i = self.indices[t*3+j];
+      (self.new_data[i*2], self.new_data[i*2+1])
+    };
+    let ((ax, ay), (bx, by), (cx, cy)) = (p(0), p(1), p(2));
+    let det = (by-cy)*(ax-cx) + (cx-bx)*(ay-cy);
+    if det==0. {
+      return None;
+    }
+    let l0 = ((by-cy)*(x-cx) + (cx-bx)*(y-cy))/det;
+    let l1 = ((cy-ay)*(x-cx) + (ax-cx)*(y-cy))/det;
+    let l2 = 1.-l0-l1;
+    const EPS: f64 = -1e-12;
+    if l0>=EPS && l1>=EPS && l2>=EPS { Some([l0, l1, l2]) } else { None }
+  }
+}
@@ -0,0 +1,55 @@
+//! Measurements of polygons
+//!
+//! Area, centroid and perimeter of the region louvre fills for a polygon:
+//! a self-intersecting polygon is measured over its resolved simple rings (see [`resolve_self_intersections`]),
+//! so that the twisted parts count with their real areas instead of cancelling out in the shoelace sum.
+
+use crate::*;
+
+
+/// Area, centroid and perimeter of a polygon.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurements {
+  pub area: f64,
+  /// Center of mass of the filled region; NaN for a region without area.
+  pub centroid: (f64, f64),
+  pub perimeter: f64,
+}
+
+/// Measures the region filled for a polygon.
+pub fn measure(data: &[f64], dim: usize) -> Measurements {
+  let rings = resolve_self_intersections(&mut data.to_vec(), dim);
+  let mut area = 0.;
+  let (mut cx, mut cy) = (0., 0.);
+  let mut perimeter = 0.;
+  for ring in rings.iter() {
+    let r = &ring.data;
+    let n = r.len()/2;
+    for i in 0..n {
+      let j = (i+1)%n;
+      let (x0, y0, x1, y1) = (r[i*2], r[i*2+1], r[j*2], r[j*2+1]);
+      let c = x0*y1 - x1*y0;
+      area += c;
+      cx += (x0+x1)*c;
+      cy += (y0+y1)*c;
+      perimeter += ((x1-x0)*(x1-x0) + (y1-y0)*(y1-y0)).sqrt();
+    }
+  }
+  area *= 0.5;
+  Measurements { area, centroid: (cx/(6.*area), cy/(6.*area)), perimeter }
+}
+
+/// Filled area of a polygon.
+pub fn filled_area(data: &[f64], dim: usize) -> f64 {
+  measure(data, dim).area
+}
+
+/// Centroid of the filled region of a polygon.
+pub fn centroid(data: &[f64], dim: usize) -> (f64, f64) {
+  measure(data, dim).centroid
+}
+
+/// Perimeter of the filled region of a polygon: the total length of its resolved rings.
+pub fn perimeter(data: &[f64], dim: usize) -> f64 {
+  measure(data, dim).perimeter
+}
@@ -0,0 +1,144 @@
+//! Polygon offsetting (buffer)
+//!
+//! Moves the boundary of a polygon outward (positive distance) or inward (negative distance).
+//!
+//! The polygon is first resolved into the region [`triangulate`] fills of it, as simple rings: outer rings are CCW and holes are CW.
+//! Then the raw offset curve is made by shifting every edge along its outer normal
+//! and by adding a join where shifted edges leave a gap, or passing through the original vertex where they overlap.
+//! The raw curve crosses itself around concave corners and wherever the polygon gets thinner than the distance;
+//! it is decomposed into louvre's cycles, and the region with a positive winding number is the offset polygon.
+
+use crate::*;
+use crate::fill::{group_polygons, resolve_winding};
+use crate::polygon::{append_mesh, polygon_region, triangulate_resolved};
+
+
+/// How the shifted edges are joined at convex corners.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JoinStyle {
+  /// Sharp corners. A miter longer than `limit * distance` (from the vertex) falls back to a bevel.
+  Miter(f64),
+  /// Arcs, whose polygons are at most the given tolerance away from the true arcs.
+  Round(f64),
+  Bevel,
+}
+
+
+/// Offsets a polygon ring by `distance`, positive outward and negative inward.
+/// The ring may be self-intersecting; its region is what [`triangulate`] fills of it.
+///
+/// Returns simple rings (with dim 2): outer rings are CCW and holes are CW.
+pub fn offset(data: &[f64], dim: usize, distance: f64, join: JoinStyle) -> Vec<Vec<f64>> {
+  offset_resolved(&polygon_region(&[data.to_vec()], dim), distance, join)
+}
+
+/// Offsets a polygon with holes (the first ring is the outer ring, the others are holes, in any winding),
+/// read the same as [`triangulate_polygon`] reads it.
+/// Holes shrink when the polygon grows, and the other way around.
+pub fn offset_polygon(rings: &[Vec<f64>], dim: usize, distance: f64, join: JoinStyle) -> Vec<Vec<f64>> {
+  offset_resolved(&polygon_region(rings, dim), distance, join)
+}
+
+/// Offsets a polygon ring (see [`offset`]) and triangulates the result.
+/// Returns a new coordinates array and a index array of it, the same as [`triangulate`].
+pub fn triangulate_offset(data: &[f64], dim: usize, distance: f64, join: JoinStyle) -> (Vec<f64>, Vec<usize>) {
+  let mut new_data: Vec<f64> = Vec::new();
+  let mut indices: Vec<usize> = Vec::new();
+  for polygon in group_polygons(offset(data, dim, distance, join)).iter() {
+    append_mesh(triangulate_resolved(polygon), &mut new_data, &mut indices);
+  }
+  (new_data, indices)
+}
+
+/// Offsets resolved rings (dim 2, outer rings CCW and holes CW).
+fn offset_resolved(rings: &[Vec<f64>], distance: f64, join: JoinStyle) -> Vec<Vec<f64>> {
+  if distance==0. || distance.is_nan() {
+    return rings.to_vec();
+  }
+  let raw: Vec<Vec<f64>> = rings.iter().map(|r| raw_offset(r, distance, join)).collect();
+  resolve_winding(&raw, |w| w>0)
+}
+
+/// The raw offset curve of a ring with dim 2.
+fn raw_offset(ring: &[f64], d: f64, join: JoinStyle) -> Vec<f64> {
+  let mut points: Vec<(f64, f64)> = Vec::new();
+  for p in ring.chunks_exact(2) {
+    if points.last()!=Some(&(p[0], p[1])) {
+      points.push((p[0], p[1]));
+    }
+  }
+  while points.len()>1 && points.first()==points.last() {
+    points.pop();
+  }
+  let len = points.len();
+  if len<3 {
+    return Vec::new();
+  }
+
+  // outer (right hand) unit normal of each edge i -> i+1
+  let normals: Vec<(f64, f64)> = (0..len).map(|i| {
+    let ((ax, ay), (bx, by)) = (points[i], points[(i+1)%len]);
+    let l = ((bx-ax)*(bx-ax) + (by-ay)*(by-ay)).sqrt();
+    ((by-ay)/l, -(bx-ax)/l)
+  }).collect();
+
+  // arc step by the sagitta: r*(1-cos(step/2)) = tolerance
+  let r = d.abs();
+  let step = match join {
+    JoinStyle::Round(tolerance) => {
+      let c = 1. - tolerance.max(r*1e-6)/r;
+      if c>-1. { (2.*c.acos()).min(f64::consts::PI/4.) } else { f64::consts::PI/4. }
+    },
+    _ => 0.,
+  };
+
+  let mut raw: Vec<f64> = Vec::with_capacity(len*4);
+  for i in 0..len {
+    let (px, py) = points[i];
+    let (n0, n1) = (normals[(i+len-1)%len], normals[i]);
+    let (v0, v1) = ((n0.0*d, n0.1*d), (n1.0*d, n1.1*d));
+    // cross and dot of the edge directions, which are the normals turned left
+    let cross = n0.0*n1.1 - n0.1*n1.0;
+    let dot = n0.0*n1.0 + n0.1*n1.1;
+    if cross==0. && dot>0. {
+      // straight
+      raw.extend([px+v1.0, py+v1.1]);
+      continue;
+    }
+    if cross*d<0. {
+      // shifted edges overlap: go through the vertex, and the winding rule cuts the loop off.
+      raw.extend([px+v0.0, py+v0.1, px, py, px+v1.0, py+v1.1]);
+      continue;
+    }
+
+    raw.extend([px+v0.0, py+v0.1]);
+    match join {
+      JoinStyle::Miter(limit) => {
+        let (mx, my) = (n0.0+n1.0, n0.1+n1.1);
+        let m2 = mx*mx + my*my;
+        if m2>0. && (4./m2).sqrt()<=limit {
+          let k = 2.*d/m2;
+          raw.extend([px+mx*k, py+my*k]);
+        }
+      },
+      JoinStyle::Round(_) => {
+        // from v0 to v1 the short way; a spike turns around its tip
+        let sweep = if cross==0. {
+          let forward = (-n0.1, n0.0);
+          f64::consts::PI * (v0.0*forward.1 - v0.1*forward.0).signum()
+        } else {
+          (v0.0*v1.1 - v0.1*v1.0).atan2(v0.0*v1.0 + v0.1*v1.1)
+        };
+        let a0 = v0.1.atan2(v0.0);
+        let n = (sweep.abs()/step).ceil() as usize;
+        for k in 1..n {
+          let a = a0 + sweep*(k as f64)/(n as f64);
+          raw.extend([px + r*a.cos(), py + r*a.sin()]);
+        }
+      },
+      JoinStyle::Bevel => {},
+    }
+    raw.extend([px+v1.0, py+v1.1]);
+  }
+  raw
+}
@@ -0,0 +1,113 @@
+//! Planar 3d polygons
+//!
+//! [`triangulate`] only looks at x and y, so a polygon standing on the xy plane (e.g. a wall) collapses to a line.
+//! [`triangulate_3d`] computes the polygon's normal by Newell's method, projects the polygon onto the dominant plane
+//! (the coordinate plane it faces the most), triangulates it there and lifts the result back to 3d.
+
+use crate::*;
+use std::collections::HashMap;
+
+
+/// Normal vector of a polygon by Newell's method; its length is twice the area of the projected polygon.
+/// Robust for non-convex and slightly non-planar polygons. `dim` must be 3 or more.
+pub fn newell_normal(data: &[f64], dim: usize) -> [f64; 3] {
+  let len = data.len()/dim;
+  let mut n = [0.; 3];
+  for i in 0..len {
+    let j = (i+1)%len;
+    let (a, b) = (&data[i*dim..i*dim+3], &data[j*dim..j*dim+3]);
+    n[0] += (a[1]-b[1])*(a[2]+b[2]);
+    n[1] += (a[2]-b[2])*(a[0]+b[0]);
+    n[2] += (a[0]-b[0])*(a[1]+b[1]);
+  }
+  n
+}
+
+/// Newell's normal, or when it vanishes (as for a figure 8 whose lobes cancel out),
+/// the normal of the widest triangle of the points: the first point, the farthest point from it,
+/// and the farthest point from the line of those two.
+fn plane_normal(data: &[f64], dim: usize) -> [f64; 3] {
+  let n = newell_normal(data, dim);
+  let p = |i: usize| [data[i*dim], data[i*dim+1], data[i*dim+2]];
+  let sub = |a: [f64; 3], b: [f64; 3]| [a[0]-b[0], a[1]-b[1], a[2]-b[2]];
+  let dot = |a: [f64; 3], b: [f64; 3]| a[0]*b[0] + a[1]*b[1] + a[2]*b[2];
+  let cross = |a: [f64; 3], b: [f64; 3]| [a[1]*b[2]-a[2]*b[1], a[2]*b[0]-a[0]*b[2], a[0]*b[1]-a[1]*b[0]];
+
+  let len = data.len()/dim;
+  let a = p(0);
+  let far = |f: &dyn Fn([f64; 3]) -> f64| (0..len).map(|i| (f(p(i)), i))
+    .fold((0., 0), |m, x| if x.0>m.0 { x } else { m });
+  let (extent, b) = far(&|q| dot(sub(q, a), sub(q, a)));
+  if dot(n, n).sqrt() > extent*1e-12 {
+    return n;
+  }
+  let ab = sub(p(b), a);
+  let (_, c) = far(&|q| { let x = cross(ab, sub(q, a)); dot(x, x) });
+  cross(ab, sub(p(c), a))
+}
+
+/// Triangulates a planar 3d polygon (`dim` 3 or more); it may be self-intersecting like for [`triangulate`].
+/// Returns a new coordinates array with dim 3 and a index array of it.
+///
+/// Triangles are counter-clockwise around the polygon's normal, i.e. they keep the input's winding.
+/// Input vertices keep their coordinates; new vertices (intersection points) get the dropped coordinate
+/// from the best-fit plane through the polygon.
+/// A degenerate polygon (all points on a line) gives empty arrays.
+pub fn triangulate_3d(data: &[f64], dim: usize) -> (Vec<f64>, Vec<usize>) {
+  if dim<3 || data.len()<dim*3 {
+    return (Vec::new(), Vec::new());
+  }
+  let n = plane_normal(data, dim);
+
+  // ----- projection ----- //
+  // Drop the axis of the largest normal component. (u, v, w) is a right handed frame, so that
+  // counter-clockwise on the (u, v) plane means counter-clockwise around the normal.
+  let drop = if n[0].abs()>=n[1].abs() && n[0].abs()>=n[2].abs() {
+    0
+  } else if n[1].abs()>=n[2].abs() {
+    1
+  } else {
+    2
+  };
+  if n[drop]==0. {
+    return (Vec::new(), Vec::new());
+  }
+  let (u, v) = match (drop, n[drop]>0.) {
+    (0, true) => (1, 2),
+    (0, false) => (2, 1),
+    (1, true) => (2, 0),
+    (1, false) => (0, 2),
+    (_, true) => (0, 1),
+    (_, false) => (1, 0),
+  };
+
+  let len = data.len()/dim;
+  let mut projected: Vec<f64> = Vec::with_capacity(len*2);
+  let mut dropped: HashMap<[u64; 2], f64> = HashMap::new();
+  let mut center = [0.; 3];
+  for p in data.chunks_exact(dim) {
+    projected.extend([p[u], p[v]]);
+    dropped.entry([(p[u]+0.).to_bits(), (p[v]+0.).to_bits()]).or_insert(p[drop]);
+    for k in 0..3 {
+      center[k] += p[k]/len as f64;
+    }
+  }
+
+  let (new_data, indices) = triangulate(&mut projected, 2);
+
+  // ----- lift ----- //
+  // plane: n.(p-center) = 0
+  let d = n[0]*center[0] + n[1]*center[1] + n[2]*center[2];
+  let lifted: Vec<f64> = new_data.chunks_exact(2).flat_map(|q| {
+    let w = match dropped.get(&[(q[0]+0.).to_bits(), (q[1]+0.).to_bits()]) {
+      Some(&w) => w,
+      None => (d - n[u]*q[0] - n[v]*q[1])/n[drop],
+    };
+    let mut p = [0.; 3];
+    p[u] = q[0];
+    p[v] = q[1];
+    p[drop] = w;
+    p
+  }).collect();
+  (lifted, indices)
+}
@@ -0,0 +1,233 @@
+//! Polygons with interior rings (holes)
+//!
+//! Louvre triangulates a single ring. A polygon with holes is brought into that shape
+//! by connecting every hole to the outer ring with a zero-width "bridge", the same way mapbox/earcut.js does.
+//! The outer ring is read the way [`triangulate`] fills it and the holes are cut out of it, so rings may cross themselves or each other.
+
+use crate::*;
+use crate::fill::{group_polygons, resolve_winding};
+use crate::triangulate::{side, ROUNDING};
+
+
+/// Triangulates a polygon which has interior rings (holes).
+/// Returns a new coordinates array and a index array of it, the same as [`triangulate`].
+///
+/// # Arguments
+/// * `rings` - The first ring is the outer ring, the others are holes. Each ring is a coordinates array like `data` of [`triangulate`].
+///   Rings may repeat their first point at the end (as GeoJSON does) and can be given in any winding direction.
+/// * `dim` - Dimension of each coordinate. Only x and y are used.
+pub fn triangulate_polygon(rings: &[Vec<f64>], dim: usize) -> (Vec<f64>, Vec<usize>) {
+  if rings.len()<=1 {
+    let mut data: Vec<f64> = rings.first().map(|r| ring_points(r, dim, true).into_iter().flat_map(|(x, y)| [x, y]).collect()).unwrap_or_default();
+    if data.len()<6 {
+      return (Vec::new(), Vec::new());
+    }
+    return triangulate(&mut data, 2);
+  }
+
+  // The outer ring is filled the way `triangulate` fills it, and the holes are cut out of it.
+  let mut new_data: Vec<f64> = Vec::new();
+  let mut indices: Vec<usize> = Vec::new();
+  for polygon in group_polygons(polygon_region(rings, dim)).iter() {
+    append_mesh(triangulate_resolved(polygon), &mut new_data, &mut indices);
+  }
+  (new_data, indices)
+}
+
+/// Simple rings (with dim 2) of the region a polygon with holes fills (outer rings CCW and holes CW):
+/// what [`triangulate`] fills of the outer ring, less what it fills of any hole.
+pub(crate) fn polygon_region(rings: &[Vec<f64>], dim: usize) -> Vec<Vec<f64>> {
+  // The union of louvre's cycles of the rings. `triangulate` fills a cycle whichever way it runs,
+  // as well as the loops where it passes a point twice, so each cycle is read with its winding number nonzero.
+  let filled = |rings: &[Vec<f64>]| -> Vec<Vec<f64>> {
+    let cycles: Vec<Vec<f64>> = rings.iter().flat_map(|r| {
+      let mut r: Vec<f64> = ring_points(r, dim, true).into_iter().flat_map(|(x, y)| [x, y]).collect();
+      if r.len()<6 { Vec::new() } else { make_valid(&mut r, 2) }
+    }).flat_map(|c| resolve_winding(&[c], |w| w!=0)).collect();
+    resolve_winding(&cycles, |w| w>0)
+  };
+  let Some(outer) = rings.first() else { return Vec::new() };
+  let region = filled(std::slice::from_ref(outer));
+  if rings.len()==1 {
+    return region;
+  }
+  // the holes wind the other way, to cut out of the outer region
+  let holes = filled(&rings[1..]);
+  let rings: Vec<Vec<f64>> = region.into_iter()
+    .chain(holes.iter().map(|h| h.chunks_exact(2).rev().flatten().copied().collect()))
+    .collect();
+  resolve_winding(&rings, |w| w>0)
+}
+
+/// Triangulates a polygon whose rings are simple and don't cross each other:
+/// the outer ring is CCW and holes are CW, with dim 2.
+pub(crate) fn triangulate_resolved(rings: &[Vec<f64>]) -> (Vec<f64>, Vec<usize>) {
+  triangulate_resolved_with(rings, Backend::EarCut)
+}
+
+/// [`triangulate_resolved`] with a chosen [`Backend`].
+pub(crate) fn triangulate_resolved_with(rings: &[Vec<f64>], backend: Backend) -> (Vec<f64>, Vec<usize>) {
+  let mut data = eliminate_holes(rings, 2);
+  if data.len()<6 {
+    return (Vec::new(), Vec::new());
+  }
+  triangulate_simple_with(&mut data, 2, backend)
+}
+
+/// Merges holes into the outer ring with bridges and returns a single ring (with `dim` of 2).
+/// The outer ring gets CCW winding and each hole gets CW winding before being linked.
+pub fn eliminate_holes(rings: &[Vec<f64>], dim: usize) -> Vec<f64> {
+  let Some(outer) = rings.first() else { return Vec::new() };
+  let mut outer = ring_points(outer, dim, true);
+  if outer.len()<3 {
+    return Vec::new();
+  }
+
+  let mut holes: Vec<Vec<(f64, f64)>> = rings.iter().skip(1)
+    .map(|r| ring_points(r, dim, false))
+    .filter(|r| r.len()>=3)
+    .collect();
+
+  // Link holes from the left to the right. Each hole is linked through its leftmost point.
+  let leftmost = |ring: &Vec<(f64, f64)>| -> usize {
+    let mut l = 0;
+    for (e, p) in ring.iter().enumerate() {
+      if p.0<ring[l].0 || (p.0==ring[l].0 && p.1<ring[l].1) {
+        l = e;
+      }
+    }
+    l
+  };
+  holes.sort_by(|a, b| a[leftmost(a)].0.partial_cmp(&b[leftmost(b)].0).unwrap_or(Ordering::Equal));
+
+  for hole in holes.iter() {
+    let h = leftmost(hole);
+    if let Some(m) = find_hole_bridge(hole[h], &outer) {
+      // outer[..=m] -> hole[h..] -> hole[..=h] -> outer[m..]
+      let mut ring: Vec<(f64, f64)> = Vec::with_capacity(outer.len()+hole.len()+2);
+      ring.extend_from_slice(&outer[..=m]);
+      ring.extend_from_slice(&hole[h..]);
+      ring.extend_from_slice(&hole[..=h]);
+      ring.extend_from_slice(&outer[m..]);
+      outer = ring;
+    }
+  }
+
+  outer.into_iter().flat_map(|(x, y)| [x, y]).collect()
+}
+
+/// Appends a mesh (`new_data` with dim 2 and `indices`) to another, shifting its indices.
+pub(crate) fn append_mesh(mesh: (Vec<f64>, Vec<usize>), new_data: &mut Vec<f64>, indices: &mut Vec<usize>) {
+  let offset = new_data.len()/2;
+  new_data.extend(mesh.0);
+  indices.extend(mesh.1.into_iter().map(|i| i+offset));
+}
+
+/// Collects (x, y) points of a ring, dropping the closing point.
+/// Reverses the ring if its winding is not the wanted one (ccw: true => CCW).
+pub(crate) fn ring_points(data: &[f64], dim: usize, ccw: bool) -> Vec<(f64, f64)> {
+  if dim<2 {
+    return Vec::new();
+  }
+  let mut points: Vec<(f64, f64)> = data.chunks_exact(dim).map(|c| (c[0], c[1])).collect();
+  while points.len()>1 && points[0]==points[points.len()-1] {
+    points.pop();
+  }
+  let flat: Vec<f64> = points.iter().flat_map(|p| [p.0, p.1]).collect();
+  if flat.len()>=6 {
+    let reverse = match signed_area(&flat, 2) {
+      Winding::CCW => !ccw,
+      Winding::CW => ccw,
+      Winding::Zero => false,
+    };
+    if reverse {
+      points.reverse();
+    }
+  }
+  points
+}
+
+fn cross(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> f64 {
+  (b.1-a.1)*(c.0-b.0) - (b.0-a.0)*(c.1-b.1)
+}
+
+fn point_in_triangle(a: (f64, f64), b: (f64, f64), c: (f64, f64), p: (f64, f64)) -> bool {
+  (c.0-p.0)*(a.1-p.1) >= (a.0-p.0)*(c.1-p.1) &&
+  (a.0-p.0)*(b.1-p.1) >= (b.0-p.0)*(a.1-p.1) &&
+  (b.0-p.0)*(c.1-p.1) >= (c.0-p.0)*(b.1-p.1)
+}
+
+/* Is the diagonal (a, b) locally inside the polygon at a? */
+fn locally_inside(ring: &[(f64, f64)], a: usize, b: (f64, f64)) -> bool {
+  let n = ring.len();
+  let (prev, p, next) = (ring[(a+n-1)%n], ring[a], ring[(a+1)%n]);
+  if cross(prev, p, next)<0. {
+    cross(p, b, next)>=0. && cross(p, prev, b)>=0.
+  } else {
+    cross(p, b, prev)<0. || cross(p, next, b)<0.
+  }
+}
+
+/* Does the sector at m contain the sector at p? */
+fn sector_contains_sector(ring: &[(f64, f64)], m: usize, p: usize) -> bool {
+  let n = ring.len();
+  cross(ring[(m+n-1)%n], ring[m], ring[(p+n-1)%n])<0. && cross(ring[(p+1)%n], ring[m], ring[(m+1)%n])<0.
+}
+
+/* Find a vertex of the outer ring which can be linked to the hole's leftmost point (hole) without crossing any edge. */
+fn find_hole_bridge(hole: (f64, f64), outer: &[(f64, f64)]) -> Option<usize> {
+  let n = outer.len();
+  let (hx, hy) = hole;
+  let mut qx = f64::NEG_INFINITY;
+  let mut m: Option<usize> = None;
+
+  // Find a segment intersected by a ray from the hole's leftmost point to the left.
+  // The segment's endpoint with lesser x will be the potential connection point.
+  for i in 0..n {
+    let (p, q) = (outer[i], outer[(i+1)%n]);
+    if p==hole {
+      return Some(i);
+    }
+    if hy<=p.1 && hy>=q.1 && q.1!=p.1 {
+      let x = p.0 + (hy-p.1)*(q.0-p.0)/(q.1-p.1);
+      if x<=hx && x>qx {
+        qx = x;
+        let e = if p.0<q.0 { i } else { (i+1)%n };
+        if x==hx {
+          return Some(e); // the hole touches the outer segment.
+        }
+        m = Some(e);
+      }
+    }
+  }
+  let mut m = m?;
+
+  // Look for points inside the triangle of the hole point, the segment intersection and the endpoint.
+  // If there is none, the connection is valid. Otherwise choose the point of the minimum angle with the ray.
+  // Points found on segments are rounded, so points in line with the hole point and the endpoint count as inside,
+  // and points in line with the hole point and the last choice tie.
+  let stop = m;
+  let (mx, my) = outer[m];
+  let mut tan_min = f64::INFINITY;
+  let mut i = m;
+  loop {
+    let p = outer[i];
+    if hx>=p.0 && p.0>=mx && hx!=p.0 &&
+      (point_in_triangle(
+        (if hy<my {hx} else {qx}, hy), (mx, my), (if hy<my {qx} else {hx}, hy), p
+      ) || side(hx,hy, mx,my, p.0,p.1).abs()<=ROUNDING) {
+      let tan = (hy-p.1).abs()/(hx-p.0);
+      let tie = tan_min.is_finite() && side(hx,hy, outer[m].0,outer[m].1, p.0,p.1).abs()<=ROUNDING;
+      if locally_inside(outer, i, hole) &&
+        ((tan<tan_min && !tie) || (tie && (p.0>outer[m].0 || (p.0==outer[m].0 && sector_contains_sector(outer, m, i))))) {
+        m = i;
+        tan_min = tan;
+      }
+    }
+    i = (i+1)%n;
+    if i==stop {
+      break;
+    }
+  }
+  Some(m)
+}
@@ -0,0 +1,165 @@
+//! SVG rendering for debugging
+//!
+//! Renders an input outline, its triangulation and the detected intersection points into an SVG string,
+//! so that a triangulation can be dumped to a file and inspected without a browser canvas (cf. `html::DrawLouvre`).
+//!
+//! # Ex
+//! ```rust
+//! use louvre::{triangulate, render::{render_svg, SvgOptions}};
+//!
+//! let mut data: Vec<f64> = vec![0.,0., 0.,3., 3.,0., 3.,4., -1.,0.];
+//! let (new_data, indices) = triangulate(&mut data.clone(), 2);
+//! let svg = render_svg(&data, 2, &new_data, &indices, &SvgOptions::default());
+//! assert!(svg.starts_with("<svg"));
+//! // std::fs::write("debug.svg", svg).unwrap();
+//! ```
+
+use crate::*;
+use std::fmt::Write;
+
+
+/// Options of [`render_svg`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgOptions {
+  /// Size (px) of the longer side of the drawing, margins excluded.
+  pub size: f64,
+  /// Margin (px) around the drawing.
+  pub margin: f64,
+  /// Fill colour of each triangle (any SVG colour). Cycled when shorter than the triangles.
+  /// If `None`, colours are generated from the triangle index.
+  pub colors: Option<Vec<String>>,
+  /// Labels each triangle with its index (in the `indices` array divided by 3).
+  pub labels: bool,
+  /// Marks the self-intersection points of the outline.
+  pub intersections: bool,
+  /// Flips the y axis, so that y goes up as in math coordinates.
+  pub flip_y: bool,
+}
+
+impl Default for SvgOptions {
+  fn default() -> Self {
+    SvgOptions {
+      size: 800.,
+      margin: 20.,
+      colors: None,
+      labels: false,
+      intersections: true,
+      flip_y: false,
+    }
+  }
+}
+
+
+/// Renders an outline (`data` with `dim`) and its triangulation (`new_data` with dim 2 and `indices`) into an SVG document.
+///
+/// Drawn from bottom to top: triangles, the outline, intersection points and labels.
+/// Either of the outline or the triangulation can be empty.
+pub fn render_svg(data: &[f64], dim: usize, new_data: &[f64], indices: &[usize], options: &SvgOptions) -> String {
+  let outline: Vec<f64> = data.chunks_exact(dim.max(2)).flat_map(|p| [p[0], p[1]]).collect();
+  let sects = if options.intersections && outline.len()>=6 {
+    intersection_points(&mut outline.clone(), 2)
+  } else {
+    Vec::new()
+  };
+
+  // ----- viewport ----- //
+  let (mut x0, mut y0, mut x1, mut y1) = (f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+  for p in outline.chunks_exact(2).chain(new_data.chunks_exact(2)) {
+    x0 = x0.min(p[0]);
+    y0 = y0.min(p[1]);
+    x1 = x1.max(p[0]);
+    y1 = y1.max(p[1]);
+  }
+  if x0>x1 {
+    (x0, y0, x1, y1) = (0., 0., 0., 0.);
+  }
+  let extent = (x1-x0).max(y1-y0);
+  let r = if extent>0. { options.size/extent } else { 1. };
+  let m = options.margin;
+  let width = (x1-x0)*r + m*2.;
+  let height = (y1-y0)*r + m*2.;
+  let tx = |x: f64| (x-x0)*r + m;
+  let ty = |y: f64| if options.flip_y { (y1-y)*r + m } else { (y-y0)*r + m };
+
+  let mut svg = String::new();
+  let _ = writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{:.0}" height="{:.0}" viewBox="0 0 {:.2} {:.2}">"#,
+    width.ceil(), height.ceil(), width, height);
+  let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#);
+
+  // ----- triangles ----- //
+  let _ = writeln!(svg, r##"<g stroke="#333" stroke-width="0.5" stroke-linejoin="round">"##);
+  for (e, t) in indices.chunks_exact(3).enumerate() {
+    let fill = match &options.colors {
+      Some(colors) if !colors.is_empty() => escape_attribute(&colors[e%colors.len()]),
+      _ => color(e),
+    };
+    let _ = write!(svg, r#"<polygon fill="{}" points=""#, fill);
+    for (k, &i) in t.iter().enumerate() {
+      let _ = write!(svg, "{}{:.2},{:.2}", if k>0 { " " } else { "" }, tx(new_data[i*2]), ty(new_data[i*2+1]));
+    }
+    let _ = writeln!(svg, r#""/>"#);
+  }
+  let _ = writeln!(svg, "</g>");
+
+  // ----- outline ----- //
+  if outline.len()>=4 {
+    let _ = write!(svg, r##"<polygon fill="none" stroke="#000" stroke-width="1.5" points=""##);
+    for (k, p) in outline.chunks_exact(2).enumerate() {
+      let _ = write!(svg, "{}{:.2},{:.2}", if k>0 { " " } else { "" }, tx(p[0]), ty(p[1]));
+    }
+    let _ = writeln!(svg, r#""/>"#);
+  }
+
+  // ----- intersections ----- //
+  if !sects.is_empty() {
+    let _ = writeln!(svg, r#"<g fill="red">"#);
+    for p in sects.chunks_exact(2) {
+      let _ = writeln!(svg, r#"<circle cx="{:.2}" cy="{:.2}" r="3"/>"#, tx(p[0]), ty(p[1]));
+    }
+    let _ = writeln!(svg, "</g>");
+  }
+
+  // ----- labels ----- //
+  if options.labels {
+    let _ = writeln!(svg, r#"<g font-family="sans-serif" font-size="10" text-anchor="middle" dominant-baseline="middle">"#);
+    for (e, t) in indices.chunks_exact(3).enumerate() {
+      let cx = t.iter().map(|&i| new_data[i*2]).sum::<f64>()/3.;
+      let cy = t.iter().map(|&i| new_data[i*2+1]).sum::<f64>()/3.;
+      let _ = writeln!(svg, r#"<text x="{:.2}" y="{:.2}">{}</text>"#, tx(cx), ty(cy), e);
+    }
+    let _ = writeln!(svg, "</g>");
+  }
+
+  svg.push_str("</svg>\n");
+  svg
+}
+
+/// Triangulates `data` and renders it with [`render_svg`].
+pub fn render_triangulation(data: &[f64], dim: usize, options: &SvgOptions) -> String {
+  if data.len()<dim*3 {
+    return render_svg(data, dim, &[], &[], options);
+  }
+  let (new_data, indices) = triangulate(&mut data.to_vec(), dim);
+  render_svg(data, dim, &new_data, &indices, options)
+}
+
+/// Escapes a value for a double-quoted XML attribute.
+fn escape_attribute(value: &str) -> String {
+  let mut escaped = String::with_capacity(value.len());
+  for c in value.chars() {
+    match c {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      _ => escaped.push(c),
+    }
+  }
+  escaped
+}
+
+/// A pastel colour for the i-th triangle; neighbouring indices get distinct hues.
+fn color(i: usize) -> String {
+  let hue = (i as f64 * 137.508) % 360.;
+  format!("hsl({:.0},70%,75%)", hue)
+}
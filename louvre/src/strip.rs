@@ -0,0 +1,160 @@
+//! Triangle strips and fans
+//!
+//! Converts a triangle list into `TRIANGLE_STRIP` index buffers for GPU pipelines which prefer them,
+//! and convex cycles into `TRIANGLE_FAN`s.
+//!
+//! Strips are grown greedily over the [`HalfEdgeMesh`] of the triangles: starting from the triangles with the fewest
+//! neighbours, each strip goes on across the edge of its last two vertices, in the direction which makes it longest.
+//! Strips keep the counter-clockwise winding of the triangles, with the usual flip of every other triangle.
+
+use crate::*;
+use crate::halfedge::HalfEdgeMesh;
+use crate::polygon::append_mesh;
+
+
+/// How consecutive strips are put in one index buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StripJoin {
+  /// Separated by a primitive restart index (e.g. `u32::MAX as usize`).
+  Restart(usize),
+  /// Linked by repeated indices, making degenerate triangles which are not drawn.
+  Degenerate,
+}
+
+/// Strips and fans over a coordinates array.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Primitives {
+  /// Coordinates array with dim 2.
+  pub positions: Vec<f64>,
+  /// One index buffer of all strips, joined by [`StripJoin`].
+  pub strips: Vec<usize>,
+  /// Number of strips in `strips`.
+  pub strip_count: usize,
+  /// Index buffers of fans, one per convex cycle.
+  pub fans: Vec<Vec<usize>>,
+  /// Number of indices the same triangles take as a triangle list.
+  pub list_len: usize,
+}
+
+impl Primitives {
+  /// Number of indices of the strips and fans.
+  pub fn index_count(&self) -> usize {
+    self.strips.len() + self.fans.iter().map(|f| f.len()).sum::<usize>()
+  }
+
+  /// Indices saved compared to a triangle list (negative if there are more).
+  pub fn saved(&self) -> isize {
+    self.list_len as isize - self.index_count() as isize
+  }
+}
+
+
+/// Converts a triangle list (a coordinates array with dim 2 and a index array of it, like the output of [`triangulate`])
+/// into strips. Vertices with the same position are welded first, see [`HalfEdgeMesh`].
+pub fn stripify(new_data: &[f64], indices: &[usize], join: StripJoin) -> Primitives {
+  let mesh = HalfEdgeMesh::new(new_data, indices);
+  let (strips, strip_count) = strips(&mesh, join);
+  Primitives { list_len: mesh.indices.len(), positions: mesh.positions, strips, strip_count, fans: Vec::new() }
+}
+
+/// Triangulates a polygon (see [`triangulate`]) into fans for its convex cycles, and strips for the others.
+pub fn triangulate_strips(data: &mut Vec<f64>, dim: usize, join: StripJoin) -> Primitives {
+  let mut new_data: Vec<f64> = Vec::new();
+  let mut indices: Vec<usize> = Vec::new();
+  let mut fan_rings: Vec<Vec<f64>> = Vec::new();
+  for ring in resolve_self_intersections(data, dim).into_iter() {
+    if is_convex(&ring.data) {
+      fan_rings.push(ring.data);
+    } else {
+      append_mesh(triangulate_simple(&mut ring.data.clone(), 2), &mut new_data, &mut indices);
+    }
+  }
+
+  let mut primitives = stripify(&new_data, &indices, join);
+  for ring in fan_rings.into_iter() {
+    let n = ring.len()/2;
+    let first = primitives.positions.len()/2;
+    primitives.positions.extend(ring);
+    primitives.fans.push((first..first+n).collect());
+    primitives.list_len += (n-2)*3;
+  }
+  primitives
+}
+
+/// A CCW ring without reflex vertices (with dim 2).
+fn is_convex(ring: &[f64]) -> bool {
+  let n = ring.len()/2;
+  n>=3 && (0..n).all(|i| {
+    let (a, b, c) = (i, (i+1)%n, (i+2)%n);
+    area(ring[a*2], ring[a*2+1], ring[b*2], ring[b*2+1], ring[c*2], ring[c*2+1])!=Winding::CW
+  })
+}
+
+/// Strips over the mesh joined in one index buffer, and their number.
+fn strips(mesh: &HalfEdgeMesh, join: StripJoin) -> (Vec<usize>, usize) {
+  let n = mesh.triangle_count();
+  let mut visited: Vec<bool> = vec![false; n];
+  // triangles with fewer neighbours are harder to reach later, so they start first.
+  let mut starts: Vec<usize> = (0..n).collect();
+  starts.sort_by_key(|&t| mesh.neighbours(t).count());
+
+  // triangles taken by the strip being grown, by the number of the try
+  let mut taken: Vec<usize> = vec![0; n];
+  let mut tries = 0;
+
+  let mut buffer: Vec<usize> = Vec::new();
+  let mut count = 0;
+  for &t in starts.iter() {
+    if visited[t] {
+      continue;
+    }
+    // the longest of the three ways to enter the triangle
+    let (strip, triangles) = (0..3).map(|k| {
+      tries += 1;
+      grow(mesh, &visited, &mut taken, tries, t*3+k)
+    }).fold((Vec::new(), Vec::new()), |best, s| if s.0.len()>best.0.len() { s } else { best });
+    for t in triangles {
+      visited[t] = true;
+    }
+
+    if count>0 {
+      match join {
+        StripJoin::Restart(r) => buffer.push(r),
+        StripJoin::Degenerate => {
+          // keep the next strip starting at an even position, so its winding stays
+          let odd = buffer.len()%2==1;
+          buffer.push(*buffer.last().unwrap());
+          buffer.push(strip[0]);
+          if odd {
+            buffer.push(strip[0]);
+          }
+        },
+      }
+    }
+    buffer.extend(strip);
+    count += 1;
+  }
+  (buffer, count)
+}
+
+/// A strip starting with triangle h/3, entered so that its first vertex is the origin of h.
+/// Returns the strip and its triangles; the triangles are marked in `taken` by `id`.
+fn grow(mesh: &HalfEdgeMesh, visited: &[bool], taken: &mut [usize], id: usize, h: usize) -> (Vec<usize>, Vec<usize>) {
+  let mut strip: Vec<usize> = vec![mesh.origin(h), mesh.target(h), mesh.target(mesh.next(h))];
+  let mut triangles: Vec<usize> = vec![h/3];
+  taken[h/3] = id;
+  // the half-edge of the last triangle on the edge of its last two vertices
+  let mut e = mesh.next(h);
+  loop {
+    let g = match mesh.twin(e) {
+      Some(g) if !visited[g/3] && taken[g/3]!=id => g,
+      _ => break,
+    };
+    taken[g/3] = id;
+    triangles.push(g/3);
+    strip.push(mesh.target(mesh.next(g)));
+    // after an odd triangle (s1, s0, s2) the next edge is s2 -> s1, after an even one (s0, s1, s2) it is s1 -> s2.
+    e = if triangles.len()%2==1 { mesh.next(g) } else { mesh.prev(g) };
+  }
+  (strip, triangles)
+}
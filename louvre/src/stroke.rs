@@ -0,0 +1,204 @@
+//! Stroke tessellation
+//!
+//! Turns a polyline or a ring into the triangles of its stroke, like Canvas2D/SVG `stroke()` draws it,
+//! for renderers (WebGL, wgpu...) without a stroke primitive.
+//!
+//! The stroke is made of simple pieces: a quad per segment, a piece per join and per cap.
+//! The pieces overlap each other at joins, and wherever the line crosses itself;
+//! they are decomposed together into louvre's cycles, the same as [`triangulate`] decomposes a polygon,
+//! and their union is triangulated, so no area is covered twice.
+
+use crate::*;
+use crate::fill::{group_polygons, resolve_winding};
+use crate::polygon::{append_mesh, triangulate_resolved};
+use std::f64::consts::PI;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineJoin {
+  Miter,
+  Round,
+  Bevel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineCap {
+  Butt,
+  Round,
+  Square,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StrokeStyle {
+  pub width: f64,
+  pub join: LineJoin,
+  pub cap: LineCap,
+  /// Miter joins longer than `miter_limit * width` fall back to bevel joins.
+  pub miter_limit: f64,
+  /// Maximum distance between round joins/caps and their polygons.
+  pub tolerance: f64,
+}
+
+impl Default for StrokeStyle {
+  /// Same as the defaults of Canvas2D: width 1, miter joins with limit 10, butt caps.
+  fn default() -> Self {
+    StrokeStyle { width: 1., join: LineJoin::Miter, cap: LineCap::Butt, miter_limit: 10., tolerance: 0.01 }
+  }
+}
+
+
+/// Tessellates the stroke of an open polyline. Returns a new coordinates array (dim 2) and a index array of it.
+pub fn stroke_polyline(data: &[f64], dim: usize, style: &StrokeStyle) -> (Vec<f64>, Vec<usize>) {
+  triangulate_pieces(&stroke_pieces(data, dim, style, false))
+}
+
+/// Tessellates the stroke of a closed ring: joins at every vertex, and no caps.
+pub fn stroke_ring(data: &[f64], dim: usize, style: &StrokeStyle) -> (Vec<f64>, Vec<usize>) {
+  triangulate_pieces(&stroke_pieces(data, dim, style, true))
+}
+
+/// Triangulates the union of the pieces, where their winding number is positive.
+fn triangulate_pieces(pieces: &[Vec<f64>]) -> (Vec<f64>, Vec<usize>) {
+  let mut new_data: Vec<f64> = Vec::new();
+  let mut indices: Vec<usize> = Vec::new();
+  for polygon in group_polygons(resolve_winding(pieces, |w| w>0)).iter() {
+    append_mesh(triangulate_resolved(polygon), &mut new_data, &mut indices);
+  }
+  (new_data, indices)
+}
+
+/// Simple counter-clockwise polygons whose union is the stroke.
+pub fn stroke_pieces(data: &[f64], dim: usize, style: &StrokeStyle, closed: bool) -> Vec<Vec<f64>> {
+  let hw = style.width*0.5;
+  let mut pieces: Vec<Vec<f64>> = Vec::new();
+  if hw.is_nan() || hw<=0. {
+    return pieces;
+  }
+
+  // drop repeated points (and the closing point of a ring)
+  let mut points: Vec<(f64, f64)> = Vec::new();
+  for p in data.chunks_exact(dim) {
+    if points.last()!=Some(&(p[0], p[1])) {
+      points.push((p[0], p[1]));
+    }
+  }
+  if closed && points.len()>1 && points.first()==points.last() {
+    points.pop();
+  }
+  let closed = closed && points.len()>2;
+
+  // arc step by the sagitta: hw*(1-cos(step/2)) = tolerance
+  let step = {
+    let c = 1. - style.tolerance.max(1e-9)/hw;
+    if c>-1. { (2.*c.acos()).min(PI/4.) } else { PI/4. }
+  };
+  // points inside an arc; the end points are left to the caller, to keep them exact.
+  let arc = |piece: &mut Vec<f64>, (cx, cy): (f64, f64), a0: f64, sweep: f64| {
+    let n = ((sweep.abs()/step).ceil() as usize).max(1);
+    for k in 1..n {
+      let a = a0 + sweep*(k as f64)/(n as f64);
+      piece.extend([cx + hw*a.cos(), cy + hw*a.sin()]);
+    }
+  };
+
+  if points.len()==1 {
+    // a lone point: only round and square caps show up
+    let (x, y) = points[0];
+    match style.cap {
+      LineCap::Butt => {},
+      LineCap::Round => {
+        let mut piece: Vec<f64> = vec![x+hw, y];
+        arc(&mut piece, (x, y), 0., PI*2.);
+        pieces.push(piece);
+      },
+      LineCap::Square => pieces.push(vec![x-hw,y-hw, x+hw,y-hw, x+hw,y+hw, x-hw,y+hw]),
+    }
+    return pieces;
+  }
+
+  // ----- segments ----- //
+  let segments = if closed { points.len() } else { points.len()-1 };
+  // unit direction of each segment
+  let dirs: Vec<(f64, f64)> = (0..segments).map(|i| {
+    let ((ax, ay), (bx, by)) = (points[i], points[(i+1)%points.len()]);
+    let len = ((bx-ax)*(bx-ax) + (by-ay)*(by-ay)).sqrt();
+    ((bx-ax)/len, (by-ay)/len)
+  }).collect();
+  for (i, &(dx, dy)) in dirs.iter().enumerate() {
+    let ((ax, ay), (bx, by)) = (points[i], points[(i+1)%points.len()]);
+    let (nx, ny) = (-dy*hw, dx*hw);
+    pieces.push(vec![ax+nx,ay+ny, ax-nx,ay-ny, bx-nx,by-ny, bx+nx,by+ny]);
+  }
+
+  // ----- joins ----- //
+  let joins: Vec<usize> = if closed { (0..points.len()).collect() } else { (1..points.len()-1).collect() };
+  for i in joins {
+    let (d0, d1) = (dirs[(i+segments-1)%segments], dirs[i%segments]);
+    let (px, py) = points[i];
+    let cross = d0.0*d1.1 - d0.1*d1.0;
+    let dot = d0.0*d1.0 + d0.1*d1.1;
+    if cross==0. && dot>0. {
+      continue;
+    }
+    // outer normals of the incoming and outgoing segments
+    let s = if cross<0. { -1. } else { 1. };
+    let (o0, o1) = ((d0.1*s, -d0.0*s), (d1.1*s, -d1.0*s));
+    let (p0, p1) = ((px+o0.0*hw, py+o0.1*hw), (px+o1.0*hw, py+o1.1*hw));
+    match style.join {
+      LineJoin::Round => {
+        let a0 = o0.1.atan2(o0.0);
+        // turn from o0 to o1 the short way; a full reversal turns by pi
+        let sweep = if cross==0. { PI } else { (o0.0*o1.1 - o0.1*o1.0).atan2(o0.0*o1.0 + o0.1*o1.1) };
+        let mut piece = vec![px,py, p0.0,p0.1];
+        arc(&mut piece, (px, py), a0, sweep);
+        piece.extend([p1.0, p1.1]);
+        pieces.push(piece);
+      },
+      LineJoin::Miter | LineJoin::Bevel => {
+        if cross==0. {
+          continue;
+        }
+        // miter length / width = 1/cos(theta/2), theta: angle between the outer normals
+        let (mx, my) = (o0.0+o1.0, o0.1+o1.1);
+        let m2 = mx*mx + my*my;
+        let ratio = (4./m2).sqrt();
+        if style.join==LineJoin::Miter && ratio<=style.miter_limit {
+          let k = 2.*hw/m2;
+          pieces.push(vec![px,py, p0.0,p0.1, px+mx*k,py+my*k, p1.0,p1.1]);
+        } else {
+          pieces.push(vec![px,py, p0.0,p0.1, p1.0,p1.1]);
+        }
+      },
+    }
+  }
+
+  // ----- caps ----- //
+  if !closed {
+    let last = points.len()-1;
+    let d = dirs[segments-1];
+    for ((x, y), (dx, dy)) in [(points[0], (-dirs[0].0, -dirs[0].1)), (points[last], d)] {
+      let (nx, ny) = (-dy*hw, dx*hw);
+      match style.cap {
+        LineCap::Butt => {},
+        LineCap::Square => {
+          let (ex, ey) = (dx*hw, dy*hw);
+          pieces.push(vec![x+nx,y+ny, x-nx,y-ny, x-nx+ex,y-ny+ey, x+nx+ex,y+ny+ey]);
+        },
+        LineCap::Round => {
+          let mut piece: Vec<f64> = vec![x-nx, y-ny];
+          arc(&mut piece, (x, y), ny.atan2(nx) - PI, PI);
+          piece.extend([x+nx, y+ny]);
+          pieces.push(piece);
+        },
+      }
+    }
+  }
+
+  for piece in pieces.iter_mut() {
+    if signed_area(piece, 2)==Winding::CW {
+      let reversed: Vec<f64> = piece.chunks_exact(2).rev().flatten().copied().collect();
+      *piece = reversed;
+    }
+  }
+  pieces
+}
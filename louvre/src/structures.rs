@@ -205,7 +205,7 @@ impl RedunSect {
     let c2 = (ox-vx).powi(2) + (oy-vy).powi(2);
     let mut deno = 2.*(a2.sqrt())*(b2.sqrt());
     if deno==0. {deno+=1e-10};
-    let cos_c: f64 = (a2+b2-c2) / deno;
+    let cos_c: f64 = ((a2+b2-c2) / deno).clamp(-1., 1.); // rounding may take it just out of range on collinear segments.
     let mut angle: f64 = cos_c.acos();
     if local_wind==Winding::CW { // CCW(left turn)을 기준으로 삼기.
       angle = std::f64::consts::PI*2.-angle;
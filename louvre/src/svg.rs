@@ -0,0 +1,326 @@
+//! SVG path input
+//!
+//! Parses the `d` attribute of SVG `<path>` (all commands, absolute and relative, including arcs),
+//! flattens curves into polylines within a tolerance, and triangulates the subpaths honouring `fill-rule`.
+
+use crate::*;
+use crate::fill::{triangulate_fill, FillRule};
+use std::fmt;
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SvgError {
+  /// Unexpected character or missing number, with its position.
+  Syntax(usize),
+  /// A path must begin with a moveto command.
+  MissingMoveTo,
+}
+
+impl fmt::Display for SvgError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      SvgError::Syntax(i) => write!(f, "svg path syntax error at {}", i),
+      SvgError::MissingMoveTo => write!(f, "svg path must begin with a moveto command"),
+    }
+  }
+}
+
+impl std::error::Error for SvgError {}
+
+
+/// Parses SVG path data and triangulates it under the fill rule (SVG `fill-rule`).
+/// Open subpaths are closed implicitly, as SVG does when filling.
+///
+/// # Arguments
+/// * `d` - SVG path data, like `M0 0 L10 0 Q10 10 0 10 Z`.
+/// * `tolerance` - Maximum distance between a curve and its flattened polyline.
+pub fn triangulate_svg_path(d: &str, tolerance: f64, rule: FillRule) -> Result<(Vec<f64>, Vec<usize>), SvgError> {
+  let subpaths = parse_svg_path(d, tolerance)?;
+  Ok(triangulate_fill(&subpaths, 2, rule))
+}
+
+/// Parses SVG path data into flattened subpaths. Each subpath is a coordinates array with dim 2.
+///
+/// Curves (cubic/quadratic Béziers and elliptical arcs) are flattened so that
+/// the distance from each curve to its polyline doesn't exceed `tolerance`.
+pub fn parse_svg_path(d: &str, tolerance: f64) -> Result<Vec<Vec<f64>>, SvgError> {
+  let tol = tolerance.max(1e-9);
+  let mut s = PathScanner{ s: d.as_bytes(), i: 0 };
+  let mut subpaths: Vec<Vec<f64>> = Vec::new();
+  let mut path: Vec<f64> = Vec::new();
+
+  let mut pos = (0., 0.);
+  let mut start = (0., 0.);
+  // the last control point of C/S and Q/T, for the reflections of S and T.
+  let mut last_cubic: Option<(f64, f64)> = None;
+  let mut last_quad: Option<(f64, f64)> = None;
+  let mut cmd: Option<u8> = None;
+
+  loop {
+    s.skip_separators();
+    let Some(c) = s.peek() else { break };
+    if c.is_ascii_alphabetic() {
+      if cmd.is_none() && c!=b'm' && c!=b'M' {
+        return Err(SvgError::MissingMoveTo);
+      }
+      s.i += 1;
+      cmd = Some(c);
+      if c==b'z' || c==b'Z' {
+        // closepath
+        if path.len()>=2 {
+          subpaths.push(std::mem::take(&mut path));
+        }
+        pos = start;
+        last_cubic = None;
+        last_quad = None;
+        continue;
+      }
+    } else if cmd.is_none() {
+      return Err(SvgError::MissingMoveTo);
+    }
+    let c = match cmd {
+      Some(c) => c,
+      None => return Err(SvgError::MissingMoveTo),
+    };
+    if c==b'z' || c==b'Z' {
+      // numbers after closepath
+      return Err(SvgError::Syntax(s.i));
+    }
+    let rel = c.is_ascii_lowercase();
+    let abs = |p: (f64, f64), pos: (f64, f64)| if rel { (p.0+pos.0, p.1+pos.1) } else { p };
+
+    if path.is_empty() && c!=b'm' && c!=b'M' {
+      path.extend([pos.0, pos.1]);
+    }
+    let mut cubic: Option<(f64, f64)> = None;
+    let mut quad: Option<(f64, f64)> = None;
+
+    match c.to_ascii_uppercase() {
+      b'M' => {
+        let p = abs(s.point()?, pos);
+        if path.len()>=2 {
+          subpaths.push(std::mem::take(&mut path));
+        }
+        path.clear();
+        path.extend([p.0, p.1]);
+        pos = p;
+        start = p;
+        // subsequent pairs are lineto
+        cmd = Some(if rel { b'l' } else { b'L' });
+      },
+      b'L' => {
+        pos = abs(s.point()?, pos);
+        path.extend([pos.0, pos.1]);
+      },
+      b'H' => {
+        let x = s.number()?;
+        pos = (if rel { pos.0+x } else { x }, pos.1);
+        path.extend([pos.0, pos.1]);
+      },
+      b'V' => {
+        let y = s.number()?;
+        pos = (pos.0, if rel { pos.1+y } else { y });
+        path.extend([pos.0, pos.1]);
+      },
+      b'C' | b'S' => {
+        let c1 = if c.eq_ignore_ascii_case(&b'C') {
+          abs(s.point()?, pos)
+        } else {
+          last_cubic.map_or(pos, |l| (2.*pos.0-l.0, 2.*pos.1-l.1))
+        };
+        let c2 = abs(s.point()?, pos);
+        let p = abs(s.point()?, pos);
+        flatten_cubic(pos, c1, c2, p, tol, &mut path);
+        cubic = Some(c2);
+        pos = p;
+      },
+      b'Q' | b'T' => {
+        let c1 = if c.eq_ignore_ascii_case(&b'Q') {
+          abs(s.point()?, pos)
+        } else {
+          last_quad.map_or(pos, |l| (2.*pos.0-l.0, 2.*pos.1-l.1))
+        };
+        let p = abs(s.point()?, pos);
+        flatten_quad(pos, c1, p, tol, &mut path);
+        quad = Some(c1);
+        pos = p;
+      },
+      b'A' => {
+        let rx = s.number()?;
+        let ry = s.number()?;
+        let phi = s.number()?;
+        let large = s.flag()?;
+        let sweep = s.flag()?;
+        let p = abs(s.point()?, pos);
+        flatten_arc(pos, rx, ry, phi, large, sweep, p, tol, &mut path);
+        pos = p;
+      },
+      _ => return Err(SvgError::Syntax(s.i-1)),
+    }
+    last_cubic = cubic;
+    last_quad = quad;
+  }
+  if path.len()>=2 {
+    subpaths.push(path);
+  }
+  Ok(subpaths)
+}
+
+struct PathScanner<'a> {
+  s: &'a [u8],
+  i: usize,
+}
+
+impl PathScanner<'_> {
+  fn skip_separators(&mut self) {
+    while self.i<self.s.len() && (self.s[self.i].is_ascii_whitespace() || self.s[self.i]==b',') {
+      self.i += 1;
+    }
+  }
+
+  fn peek(&self) -> Option<u8> {
+    self.s.get(self.i).copied()
+  }
+
+  fn number(&mut self) -> Result<f64, SvgError> {
+    self.skip_separators();
+    let start = self.i;
+    let digits = |sc: &mut Self| {
+      let i = sc.i;
+      while sc.i<sc.s.len() && sc.s[sc.i].is_ascii_digit() {
+        sc.i += 1;
+      }
+      sc.i>i
+    };
+    if matches!(self.peek(), Some(b'+') | Some(b'-')) {
+      self.i += 1;
+    }
+    let mut valid = digits(self);
+    if self.peek()==Some(b'.') {
+      self.i += 1;
+      valid |= digits(self);
+    }
+    if !valid {
+      return Err(SvgError::Syntax(start));
+    }
+    if matches!(self.peek(), Some(b'e') | Some(b'E')) {
+      let e = self.i;
+      self.i += 1;
+      if matches!(self.peek(), Some(b'+') | Some(b'-')) {
+        self.i += 1;
+      }
+      if !digits(self) {
+        self.i = e; // not an exponent
+      }
+    }
+    std::str::from_utf8(&self.s[start..self.i]).ok()
+      .and_then(|x| x.parse::<f64>().ok())
+      .ok_or(SvgError::Syntax(start))
+  }
+
+  fn point(&mut self) -> Result<(f64, f64), SvgError> {
+    Ok((self.number()?, self.number()?))
+  }
+
+  /// Arc flags are single characters, which can be written without separators (`a1 1 0 00 1 1`).
+  fn flag(&mut self) -> Result<bool, SvgError> {
+    self.skip_separators();
+    match self.peek() {
+      Some(b'0') => { self.i += 1; Ok(false) },
+      Some(b'1') => { self.i += 1; Ok(true) },
+      _ => Err(SvgError::Syntax(self.i)),
+    }
+  }
+}
+
+
+// ----- flattening ----- //
+
+/// Maximum number of segments of a flattened curve.
+const MAX_SEGMENTS: f64 = 10000.;
+
+fn flatten_cubic(p0: (f64, f64), p1: (f64, f64), p2: (f64, f64), p3: (f64, f64), tol: f64, out: &mut Vec<f64>) {
+  // The flattening error of n uniform segments is at most max|B''|/(8n^2).
+  let dd = 6. * f64::max(
+    (p0.0-2.*p1.0+p2.0).hypot(p0.1-2.*p1.1+p2.1),
+    (p1.0-2.*p2.0+p3.0).hypot(p1.1-2.*p2.1+p3.1),
+  );
+  let n = (dd/(8.*tol)).sqrt().ceil().clamp(1., MAX_SEGMENTS) as usize;
+  for i in 1..n {
+    let t = i as f64/n as f64;
+    let mt = 1.-t;
+    let (a, b, c, d) = (mt*mt*mt, 3.*mt*mt*t, 3.*mt*t*t, t*t*t);
+    out.push(a*p0.0 + b*p1.0 + c*p2.0 + d*p3.0);
+    out.push(a*p0.1 + b*p1.1 + c*p2.1 + d*p3.1);
+  }
+  out.extend([p3.0, p3.1]);
+}
+
+fn flatten_quad(p0: (f64, f64), p1: (f64, f64), p2: (f64, f64), tol: f64, out: &mut Vec<f64>) {
+  let dd = 2. * (p0.0-2.*p1.0+p2.0).hypot(p0.1-2.*p1.1+p2.1);
+  let n = (dd/(8.*tol)).sqrt().ceil().clamp(1., MAX_SEGMENTS) as usize;
+  for i in 1..n {
+    let t = i as f64/n as f64;
+    let mt = 1.-t;
+    let (a, b, c) = (mt*mt, 2.*mt*t, t*t);
+    out.push(a*p0.0 + b*p1.0 + c*p2.0);
+    out.push(a*p0.1 + b*p1.1 + c*p2.1);
+  }
+  out.extend([p2.0, p2.1]);
+}
+
+/// Elliptical arc; converted from endpoint to center parameterization (SVG implementation notes F.6.5).
+#[allow(clippy::too_many_arguments)]
+fn flatten_arc(p0: (f64, f64), rx: f64, ry: f64, phi: f64, large: bool, sweep: bool, p1: (f64, f64), tol: f64, out: &mut Vec<f64>) {
+  let (mut rx, mut ry) = (rx.abs(), ry.abs());
+  if p0==p1 {
+    return;
+  }
+  if rx==0. || ry==0. {
+    out.extend([p1.0, p1.1]);
+    return;
+  }
+  let (sin, cos) = phi.to_radians().sin_cos();
+  let (dx, dy) = ((p0.0-p1.0)*0.5, (p0.1-p1.1)*0.5);
+  let x1 = cos*dx + sin*dy;
+  let y1 = -sin*dx + cos*dy;
+
+  // scale up too small radii
+  let lambda = (x1*x1)/(rx*rx) + (y1*y1)/(ry*ry);
+  if lambda>1. {
+    rx *= lambda.sqrt();
+    ry *= lambda.sqrt();
+  }
+  let num = rx*rx*ry*ry - rx*rx*y1*y1 - ry*ry*x1*x1;
+  let den = rx*rx*y1*y1 + ry*ry*x1*x1;
+  let mut coef = (num/den).max(0.).sqrt();
+  if large==sweep {
+    coef = -coef;
+  }
+  let cx1 = coef*rx*y1/ry;
+  let cy1 = -coef*ry*x1/rx;
+  let cx = cos*cx1 - sin*cy1 + (p0.0+p1.0)*0.5;
+  let cy = sin*cx1 + cos*cy1 + (p0.1+p1.1)*0.5;
+
+  let angle = |u: (f64, f64), v: (f64, f64)| (u.0*v.1-u.1*v.0).atan2(u.0*v.0+u.1*v.1);
+  let u = ((x1-cx1)/rx, (y1-cy1)/ry);
+  let v = ((-x1-cx1)/rx, (-y1-cy1)/ry);
+  let theta = angle((1., 0.), u);
+  let mut delta = angle(u, v);
+  if !sweep && delta>0. {
+    delta -= 2.*std::f64::consts::PI;
+  } else if sweep && delta<0. {
+    delta += 2.*std::f64::consts::PI;
+  }
+
+  // the sagitta of each segment must be within the tolerance.
+  let r = rx.max(ry);
+  let step = if tol<r { 2.*(1.-tol/r).acos() } else { std::f64::consts::FRAC_PI_2 };
+  let n = (delta.abs()/step).ceil().clamp(1., MAX_SEGMENTS) as usize;
+  for i in 1..n {
+    let (s, c) = (theta + delta*(i as f64)/(n as f64)).sin_cos();
+    out.push(cx + rx*c*cos - ry*s*sin);
+    out.push(cy + rx*c*sin + ry*s*cos);
+  }
+  out.extend([p1.0, p1.1]);
+}
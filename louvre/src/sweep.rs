@@ -0,0 +1,268 @@
+//! Sweep line over segments
+//!
+//! Segments which don't cross each other, ordered from left to right as a horizontal line sweeps over them,
+//! for the monotone partition (see `monotone`) and for winding numbers of regions (see `winding_numbers`).
+
+/// The sweep status: segments ordered from left to right in a treap, O(log n) expected per operation.
+/// The segments don't cross, so their order stays the same while the sweep line crosses them,
+/// and is only compared at the y of the current event. Nodes are the segments themselves, so a segment is removed without a search.
+/// Each node also keeps the sum of the weights of its subtree, for the sum of the weights left of a segment or a point.
+pub(crate) struct Status {
+  root: usize,
+  left: Vec<usize>,
+  right: Vec<usize>,
+  parent: Vec<usize>,
+  contains: Vec<bool>,
+  weight: Vec<i32>,
+  sum: Vec<i32>,
+}
+
+const NIL: usize = usize::MAX;
+
+impl Status {
+  /// A status for segments 0..n, all with weight 0.
+  pub(crate) fn new(n: usize) -> Status {
+    Status::with_weights(vec![0; n])
+  }
+
+  /// A status for segments 0..n with their weights.
+  pub(crate) fn with_weights(weight: Vec<i32>) -> Status {
+    let n = weight.len();
+    Status { root: NIL, left: vec![NIL; n], right: vec![NIL; n], parent: vec![NIL; n], contains: vec![false; n], weight, sum: vec![0; n] }
+  }
+
+  /// A fixed pseudo-random priority of a segment (splitmix64), so the output doesn't depend on a random state.
+  fn priority(e: usize) -> u64 {
+    let mut z = (e as u64).wrapping_add(0x9e3779b97f4a7c15);
+    z = (z^(z>>30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z^(z>>27)).wrapping_mul(0x94d049bb133111eb);
+    z^(z>>31)
+  }
+
+  fn sum_of(&self, e: usize) -> i32 {
+    if e==NIL { 0 } else { self.sum[e] }
+  }
+
+  fn update(&mut self, e: usize) {
+    self.sum[e] = self.sum_of(self.left[e]) + self.weight[e] + self.sum_of(self.right[e]);
+  }
+
+  /// Inserts e on the right of the segments `before` holds for, and on the left of the others.
+  pub(crate) fn insert(&mut self, e: usize, before: impl Fn(usize) -> bool) {
+    self.contains[e] = true;
+    self.left[e] = NIL;
+    self.right[e] = NIL;
+    self.parent[e] = NIL;
+    self.sum[e] = self.weight[e];
+    if self.root==NIL {
+      self.root = e;
+      return;
+    }
+    let mut node = self.root;
+    loop {
+      self.sum[node] += self.weight[e];
+      let child = if before(node) { &mut self.right[node] } else { &mut self.left[node] };
+      if *child==NIL {
+        *child = e;
+        break;
+      }
+      node = *child;
+    }
+    self.parent[e] = node;
+    while self.parent[e]!=NIL && Status::priority(self.parent[e])<Status::priority(e) {
+      self.rotate_up(e);
+    }
+  }
+
+  /// Removes e; `None` if it isn't in the status.
+  pub(crate) fn remove(&mut self, e: usize) -> Option<()> {
+    if !self.contains[e] {
+      return None;
+    }
+    self.contains[e] = false;
+    // rotate e down to a leaf, then cut it off
+    loop {
+      let (l, r) = (self.left[e], self.right[e]);
+      let child = match (l==NIL, r==NIL) {
+        (true, true) => break,
+        (false, true) => l,
+        (true, false) => r,
+        (false, false) => if Status::priority(l)>Status::priority(r) { l } else { r },
+      };
+      self.rotate_up(child);
+    }
+    let p = self.parent[e];
+    if p==NIL {
+      self.root = NIL;
+    } else if self.left[p]==e {
+      self.left[p] = NIL;
+    } else {
+      self.right[p] = NIL;
+    }
+    let mut node = p;
+    while node!=NIL {
+      self.sum[node] -= self.weight[e];
+      node = self.parent[node];
+    }
+    Some(())
+  }
+
+  /// The rightmost segment `pred` holds for, where it holds for a prefix of the segments.
+  pub(crate) fn last(&self, pred: impl Fn(usize) -> bool) -> Option<usize> {
+    let mut found = None;
+    let mut node = self.root;
+    while node!=NIL {
+      if pred(node) {
+        found = Some(node);
+        node = self.right[node];
+      } else {
+        node = self.left[node];
+      }
+    }
+    found
+  }
+
+  /// Sum of the weights of the segments left of e, which is in the status.
+  pub(crate) fn sum_before(&self, e: usize) -> i32 {
+    let mut sum = self.sum_of(self.left[e]);
+    let mut node = e;
+    while self.parent[node]!=NIL {
+      let p = self.parent[node];
+      if self.right[p]==node {
+        sum += self.sum_of(self.left[p]) + self.weight[p];
+      }
+      node = p;
+    }
+    sum
+  }
+
+  /// The segments right before and right after e, which is in the status.
+  pub(crate) fn neighbours(&self, e: usize) -> (Option<usize>, Option<usize>) {
+    let side = |down: &Vec<usize>, up: &Vec<usize>| {
+      if down[e]!=NIL {
+        let mut node = down[e];
+        while up[node]!=NIL {
+          node = up[node];
+        }
+        return Some(node);
+      }
+      let mut node = e;
+      while self.parent[node]!=NIL {
+        let p = self.parent[node];
+        if up[p]==node {
+          return Some(p);
+        }
+        node = p;
+      }
+      None
+    };
+    (side(&self.left, &self.right), side(&self.right, &self.left))
+  }
+
+  /// Rotates x above its parent.
+  fn rotate_up(&mut self, x: usize) {
+    let p = self.parent[x];
+    let g = self.parent[p];
+    if self.left[p]==x {
+      let b = self.right[x];
+      self.left[p] = b;
+      if b!=NIL {
+        self.parent[b] = p;
+      }
+      self.right[x] = p;
+    } else {
+      let b = self.left[x];
+      self.right[p] = b;
+      if b!=NIL {
+        self.parent[b] = p;
+      }
+      self.left[x] = p;
+    }
+    self.parent[p] = x;
+    self.parent[x] = g;
+    if g==NIL {
+      self.root = x;
+    } else if self.left[g]==p {
+      self.left[g] = x;
+    } else {
+      self.right[g] = x;
+    }
+    self.update(p);
+    self.update(x);
+  }
+}
+
+
+// ----- winding numbers ----- //
+
+/// A directed segment (x0, y0) -> (x1, y1) with a weight, e.g. 1 for a segment of a ring.
+pub(crate) type Segment = (f64, f64, f64, f64, i32);
+
+/// Where a winding number is wanted.
+pub(crate) enum Query {
+  /// Just left of the midpoint of a segment, which isn't horizontal.
+  LeftOf(usize),
+}
+
+/// Winding numbers at the queries: the sum of the weights of the segments crossing the ray going left from the point,
+/// positive for the ones going down (so a CCW ring of weight 1 has winding number 1 inside).
+/// The segments must not cross each other, though they may meet at their end points.
+///
+/// A `LeftOf` query gives `None` when another segment runs through the same midpoint, where left of it is ambiguous.
+/// All the queries are answered by one sweep, O((n + q) log n) expected.
+pub(crate) fn winding_numbers(segments: &[Segment], queries: &[Query]) -> Vec<Option<i32>> {
+  // segments from their lower end to their upper end; horizontal ones don't cross any ray.
+  let lower = |e: usize| {
+    let (x0, y0, x1, y1, _) = segments[e];
+    if y0<y1 { (x0, y0, x1, y1) } else { (x1, y1, x0, y0) }
+  };
+  let x_at = |e: usize, y: f64| {
+    let (xl, yl, xu, yu) = lower(e);
+    if y==yl { xl } else if y==yu { xu } else { xl + (y-yl)*(xu-xl)/(yu-yl) }
+  };
+  let slope = |e: usize| {
+    let (xl, yl, xu, yu) = lower(e);
+    (xu-xl)/(yu-yl)
+  };
+
+  // events by y: removals, then insertions, then queries.
+  let mut events: Vec<(f64, u8, usize)> = Vec::with_capacity(segments.len()*2 + queries.len());
+  for (e, &(_, y0, _, y1, _)) in segments.iter().enumerate() {
+    if y0!=y1 {
+      events.push((y0.min(y1), 1, e));
+      events.push((y0.max(y1), 0, e));
+    }
+  }
+  for (q, query) in queries.iter().enumerate() {
+    let y = match *query {
+      Query::LeftOf(e) => (segments[e].1 + segments[e].3)*0.5,
+    };
+    events.push((y, 2, q));
+  }
+  events.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+
+  let weights: Vec<i32> = segments.iter().map(|s| if s.1>s.3 { s.4 } else { -s.4 }).collect();
+  let mut status = Status::with_weights(weights);
+  let mut windings: Vec<Option<i32>> = vec![None; queries.len()];
+  for &(y, kind, k) in events.iter() {
+    match kind {
+      0 => { status.remove(k); },
+      1 => {
+        let (x, s) = (x_at(k, y), slope(k));
+        status.insert(k, |f| { let xf = x_at(f, y); xf<x || (xf==x && slope(f)<s) });
+      },
+      _ => windings[k] = match queries[k] {
+        Query::LeftOf(e) => {
+          let x = (segments[e].0 + segments[e].2)*0.5;
+          let (before, after) = status.neighbours(e);
+          let on = |f: Option<usize>| f.is_some_and(|f| {
+            let (xl, yl, xu, yu) = lower(f);
+            (x_at(f, y)-x).abs() <= (xu-xl).abs().max(yu-yl)*1e-12
+          });
+          if on(before) || on(after) { None } else { Some(status.sum_before(e)) }
+        },
+      },
+    }
+  }
+  windings
+}
@@ -7,6 +7,22 @@ use crate::*;
 /// * `data` - A vector with float64 which is a coordinates array of a certain polygon.
 /// If a polygon has 4 points of [P0(0,0), P1(1,0), P2(1,1), P3(0,1)], then the data input of it would be like [0,0, 1,0, 1,1, 0,1].
 pub fn triangulate(data: &mut Vec<f64>, dim: usize) -> (Vec<f64>, Vec<usize>){
+  triangulate_with(data, dim, Backend::EarCut)
+}
+
+/// How each simple cycle of a polygon is triangulated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backend {
+  /// Ear clipping, the same as mapbox/earcut.js. Fast for small and medium cycles.
+  #[default]
+  EarCut,
+  /// Partitioning into y-monotone pieces by a sweep line, O(n log n) per cycle.
+  /// Pays off for very large cycles, where ear clipping slows down (see the README).
+  Monotone,
+}
+
+/// [`triangulate`] with a chosen [`Backend`].
+pub fn triangulate_with(data: &mut Vec<f64>, dim: usize, backend: Backend) -> (Vec<f64>, Vec<usize>){
 
   // 1. make linked vertex list with ccw-winding.
   let mut array = linked_vertex_array(data, dim);
@@ -26,7 +42,7 @@ pub fn triangulate(data: &mut Vec<f64>, dim: usize) -> (Vec<f64>, Vec<usize>){
     // sort and link Vertex.sects;
     update_sects(&array[0]);
     // decompose into simple polygon cycles
-    (new_data, simple_cycles) = decomp_simples(&array);
+    (new_data, simple_cycles, _) = decomp_simples(&array, false);
     
   } else { 
     (new_data, simple_cycles) = decomp_simple(&array);
@@ -35,12 +51,294 @@ pub fn triangulate(data: &mut Vec<f64>, dim: usize) -> (Vec<f64>, Vec<usize>){
   // consume raw pointers
   consume_array(&array);
 
-  // 4. do earcut;
-  let indices: Vec<usize> = earcut(&simple_cycles);
+  // 4. do earcut (or monotone partitioning);
+  let indices: Vec<usize> = triangulate_cycles(&simple_cycles, backend);
 
   (new_data, indices)
 }
 
+/// Triangulates a polygon by the winding number of its regions, also returning the winding number of each triangle:
+/// how many times the polygon (made CCW as a whole) winds around it.
+/// It is 1 all over a simple polygon, 2 in the center of a pentagram, and -1 in the loops which run against the polygon
+/// (like the inner loop of a figure 8's twist), e.g. to style doubly covered areas differently.
+///
+/// A simple polygon gets the same triangles as [`triangulate`]. One crossing or touching itself is split along all of its edges,
+/// so that each triangle lies in a single region; the triangles cover the regions whose winding number isn't 0.
+pub fn triangulate_windings(data: &mut Vec<f64>, dim: usize) -> (Vec<f64>, Vec<usize>, Vec<i32>) {
+  triangulate_windings_with(data, dim, Backend::EarCut)
+}
+
+/// [`triangulate_windings`] with a chosen [`Backend`].
+pub fn triangulate_windings_with(data: &mut Vec<f64>, dim: usize, backend: Backend) -> (Vec<f64>, Vec<usize>, Vec<i32>) {
+  let ring: Vec<f64> = polygon::ring_points(data, dim, true).into_iter().flat_map(|(x, y)| [x, y]).collect();
+  if self_intersections(&mut ring.clone(), 2).is_empty() {
+    let (new_data, indices) = triangulate_with(data, dim, backend);
+    let windings = vec![1; indices.len()/3];
+    return (new_data, indices, windings);
+  }
+  fill::triangulate_windings(&[ring], backend)
+}
+
+/// Triangulates a polygon which is known to be simple, skipping the intersection check.
+/// Weakly simple polygons (e.g. a ring whose holes are linked by bridges) are fine as well.
+/// For any other polygon, use [`triangulate`].
+pub fn triangulate_simple(data: &mut Vec<f64>, dim: usize) -> (Vec<f64>, Vec<usize>) {
+  triangulate_simple_with(data, dim, Backend::EarCut)
+}
+
+/// [`triangulate_simple`] with a chosen [`Backend`].
+pub fn triangulate_simple_with(data: &mut Vec<f64>, dim: usize, backend: Backend) -> (Vec<f64>, Vec<usize>) {
+  let array = linked_vertex_array(data, dim);
+  if array.is_empty() {
+    return (Vec::new(), Vec::new());
+  }
+  let (new_data, simple_cycles) = decomp_simple(&array);
+  consume_array(&array);
+  (new_data, triangulate_cycles(&simple_cycles, backend))
+}
+
+/// Returns the self-intersection points of a polygon as a coordinates array (dim 2),
+/// the very points which [`triangulate`] inserts as new vertices.
+pub fn intersection_points(data: &mut Vec<f64>, dim: usize) -> Vec<f64> {
+  let mut array = linked_vertex_array(data, dim);
+  if array.is_empty() {
+    return Vec::new();
+  }
+  unsafe {
+    array.sort_by(|b, a| (*(*a)).top.partial_cmp(&(*(*b)).top).unwrap());
+  }
+  let mut points: Vec<(usize, f64, f64)> = Vec::new();
+  if update_intersect(&array) {
+    unsafe {
+      array.iter().for_each(|a| {
+        if let Some(sects) = &(*(*a)).sects {
+          sects.iter().for_each(|s| { points.push(((**s).i, (**s).x, (**s).y)); });
+        }
+      });
+    }
+  }
+  consume_array(&array);
+
+  // each point is shared by a pair of sects.
+  points.sort_by_key(|p| p.0);
+  points.dedup_by_key(|p| p.0);
+  points.into_iter().flat_map(|(_, x, y)| [x, y]).collect()
+}
+
+/// A place where a polygon's outline meets itself; see [`self_intersections`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SelfIntersection {
+  /// Segments of the polygon, where segment k goes from point k to point k+1 (and the last one back to point 0).
+  /// `segment_a` < `segment_b`.
+  pub segment_a: usize,
+  pub segment_b: usize,
+  pub point: (f64, f64),
+  /// Position of the point along `segment_a`, from 0 at its start to 1 at its end.
+  pub t: f64,
+  /// Position of the point along `segment_b`.
+  pub u: f64,
+  /// Whether the outline crosses itself there. If not, it only touches itself at a vertex (`t` or `u` is 0 or 1) and turns back.
+  pub crossing: bool,
+}
+
+/// Finds every place where a polygon's outline crosses or touches itself, without triangulating it.
+/// A crossing or touch at a vertex is reported once, with one of the two segments meeting there.
+///
+/// Segment indices refer to `data` as it is left by the call: a closing point equal to the first one is removed.
+pub fn self_intersections(data: &mut Vec<f64>, dim: usize) -> Vec<SelfIntersection> {
+  let ccw = signed_area(data, dim)!=Winding::CW;
+  let mut array = linked_vertex_array(data, dim);
+  if array.is_empty() {
+    return Vec::new();
+  }
+  let n = array.len();
+  unsafe {
+    array.sort_by(|b, a| (*(*a)).top.partial_cmp(&(*(*b)).top).unwrap());
+  }
+  let mut touches: Vec<(usize, usize, f64, f64)> = Vec::new();
+  let mut found: Vec<(usize, usize, f64, f64, bool)> = Vec::new();
+  if find_intersections(&array, Some(&mut touches), false) {
+    unsafe {
+      array.iter().for_each(|a| {
+        if let Some(sects) = &(*(*a)).sects {
+          // each crossing is shared by a pair of sects; take the one of the smaller vertex.
+          sects.iter().filter(|&&s| (**a).i < (*(*s).other).i).for_each(|&s| {
+            found.push(((**a).i, (*(*s).other).i, (*s).x, (*s).y, true));
+          });
+        }
+      });
+    }
+  }
+  consume_array(&array);
+  found.extend(touches.into_iter().map(|(i, j, x, y)| (i, j, x, y, false)));
+
+  // vertex i -> segment of `data`; a CW polygon is linked backward.
+  let segment = |i: usize| if ccw { i } else { (2*n-2-i)%n };
+  let point = |k: usize| (data[k*dim], data[k*dim+1]);
+  // position of (x, y) along segment k
+  let along = |k: usize, (x, y): (f64, f64)| {
+    let ((ax, ay), (bx, by)) = (point(k), point((k+1)%n));
+    ((x-ax)*(bx-ax) + (y-ay)*(by-ay)) / ((bx-ax)*(bx-ax) + (by-ay)*(by-ay))
+  };
+
+  let mut report: Vec<SelfIntersection> = found.into_iter().map(|(i, j, x, y, crossing)| {
+    let (a, b) = if segment(i)<segment(j) { (segment(i), segment(j)) } else { (segment(j), segment(i)) };
+    SelfIntersection { segment_a: a, segment_b: b, point: (x, y), t: along(a, (x, y)), u: along(b, (x, y)), crossing }
+  }).collect();
+  report.sort_by(|p, q| (p.segment_a, p.segment_b).cmp(&(q.segment_a, q.segment_b)).then(p.t.total_cmp(&q.t)));
+  report
+}
+
+/// A simple ring out of a self-intersecting polygon; see [`resolve_self_intersections`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedRing {
+  /// Coordinates array with dim 2, in CCW winding.
+  pub data: Vec<f64>,
+  /// Winding of the ring as it was traced along the polygon (made CCW as a whole) before being made CCW.
+  /// `CW` rings are the loops which run against the polygon, like the inner loop of a figure 8's twist.
+  pub winding: Winding,
+}
+
+/// Decomposes a polygon into the simple rings which [`triangulate`] fills, without triangulating them.
+/// A simple polygon gives itself back (in CCW winding).
+///
+/// The rings don't cross each other, though they may touch at intersection points.
+pub fn resolve_self_intersections(data: &mut Vec<f64>, dim: usize) -> Vec<ResolvedRing> {
+  let mut array = linked_vertex_array(data, dim);
+  if array.is_empty() {
+    return Vec::new();
+  }
+  unsafe {
+    array.sort_by(|b, a| (*(*a)).top.partial_cmp(&(*(*b)).top).unwrap());
+  }
+  let (_, simple_cycles, windings) = if update_intersect(&array) {
+    update_sects(&array[0]);
+    decomp_simples(&array, false)
+  } else {
+    let (new_data, simple_cycles) = decomp_simple(&array);
+    (new_data, simple_cycles, vec![Winding::CCW])
+  };
+  consume_array(&array);
+
+  simple_cycles.iter().zip(windings).map(|(cycle, winding)| consume_cycle_ring(cycle, winding)).collect()
+}
+
+/// Decomposes rings (with dim 2), each in its own winding, into simple cycles in the same way as [`triangulate`]:
+/// the intersections within and between the rings are linked as `Sect`s, and the cycles are traced along the rings,
+/// turning onto the crossing ring at every intersection.
+/// Unlike for a single polygon, every cycle is kept, including the ones made only of intersection points,
+/// so the winding numbers of the rings are the sums of the windings of the cycles around a point.
+///
+/// Only crossings inside segments are linked: where the rings meet at a vertex, they must not cross
+/// (rings chained by `fill::link_rings` don't). The cycles don't cross each other, though they may touch.
+pub(crate) fn decompose_rings(rings: &[Vec<f64>]) -> Vec<ResolvedRing> {
+  let (mut array, starts) = linked_rings_array(rings);
+  if array.is_empty() {
+    return Vec::new();
+  }
+  unsafe {
+    array.sort_by(|b, a| (*(*a)).top.partial_cmp(&(*(*b)).top).unwrap());
+  }
+  find_intersections(&array, None, true);
+
+  // The sign of a vertex tells if the left side of its segment is inside (by evenodd);
+  // at the top of a ring, it is the ring's own turn flipped by every other ring around the point.
+  for &start in starts.iter() {
+    unsafe {
+      let mut top = start;
+      let mut v = (*start).next;
+      while v!=start {
+        if (*v).y>(*top).y || ((*v).y==(*top).y && (*v).x<(*top).x) {
+          top = v;
+        }
+        v = (*v).next;
+      }
+      let around = starts.iter().filter(|&&s| s!=start && ring_contains(s, (*top).x, (*top).y)).count();
+      update_sects_from(top, top_turn(&top) ^ (around%2==1));
+    }
+  }
+  let (_, simple_cycles, windings) = decomp_simples(&array, true);
+  consume_array(&array);
+
+  simple_cycles.iter().zip(windings).map(|(cycle, winding)| consume_cycle_ring(cycle, winding)).collect()
+}
+
+/// Linked vertices of each ring (with dim 2) in its own winding, with indices running over all rings;
+/// returns them with a vertex of each ring. Repeated points, closing points and rings of less than 3 points are dropped.
+fn linked_rings_array<'a>(rings: &[Vec<f64>]) -> (Vec<*mut Vertex<'a>>, Vec<*mut Vertex<'a>>) {
+  let mut array: Vec<*mut Vertex> = Vec::new();
+  let mut starts: Vec<*mut Vertex> = Vec::new();
+  for ring in rings.iter() {
+    let mut points: Vec<(f64, f64)> = Vec::new();
+    for p in ring.chunks_exact(2) {
+      if points.last()!=Some(&(p[0], p[1])) {
+        points.push((p[0], p[1]));
+      }
+    }
+    while points.len()>1 && points.first()==points.last() {
+      points.pop();
+    }
+    let n = points.len();
+    if n<3 {
+      continue;
+    }
+    let mut last: *mut Vertex = ptr::null_mut();
+    for k in 0..n {
+      let ((x0, y0), (x1, y1)) = (points[k], points[(k+1)%n]);
+      last = Vertex::new(array.len(), x0, y0, x1, y1, last);
+      array.push(last);
+    }
+    starts.push(last);
+  }
+  (array, starts)
+}
+
+/// Is (x, y) inside the linked ring of `start`? (evenodd)
+fn ring_contains(start: *mut Vertex, x: f64, y: f64) -> bool {
+  let mut inside = false;
+  unsafe {
+    let mut v = start;
+    loop {
+      let (a, b) = (&*v, &*(*v).next);
+      if (a.y>y) != (b.y>y) && x < a.x + (y-a.y)*(b.x-a.x)/(b.y-a.y) {
+        inside = !inside;
+      }
+      v = (*v).next;
+      if v==start {
+        break;
+      }
+    }
+  }
+  inside
+}
+
+/// Points of a cycle as a coordinates array with dim 2, consuming them.
+fn consume_cycle_ring(cycle: &SimpleCycle, winding: Winding) -> ResolvedRing {
+  let mut ring: Vec<f64> = Vec::new();
+  unsafe {
+    // points are linked after the last one made.
+    let last = cycle.point;
+    let mut p = (*last).next;
+    loop {
+      ring.push((*p).x);
+      ring.push((*p).y);
+      let next = (*p).next;
+      drop(Box::from_raw(p));
+      if p==last {
+        break;
+      }
+      p = next;
+    }
+  }
+  ResolvedRing{ data: ring, winding }
+}
+
+/// Simple rings (dim 2, CCW) of a polygon; [`resolve_self_intersections`] without the windings.
+/// They can be written back as the polygons of a valid (OGC) MultiPolygon.
+pub fn make_valid(data: &mut Vec<f64>, dim: usize) -> Vec<Vec<f64>> {
+  resolve_self_intersections(data, dim).into_iter().map(|r| r.data).collect()
+}
+
 /// Consume raw pointers;
 fn consume_array(array: &Vec<*mut Vertex>) {
   unsafe {
@@ -75,8 +373,8 @@ fn is_reflex(prev: *mut Point, v: *mut Point, next: *mut Point) -> bool {
   }
 }
 
-/* Is it earcut-t-able? */
-fn is_ear(prev: *mut Point, v: *mut Point, next: *mut Point) -> bool {
+/* Is it earcut-t-able? `repeats` tells the cycle repeats a point (see `blocks_ear`). */
+fn is_ear(prev: *mut Point, v: *mut Point, next: *mut Point, repeats: bool) -> bool {
   unsafe {
     // (1) Is it reflex? If so update the state, if still so, skip it.
     if (*v).reflex {
@@ -97,11 +395,22 @@ fn is_ear(prev: *mut Point, v: *mut Point, next: *mut Point) -> bool {
     let y1 = f64::max(f64::max(ay, by), cy);
 
     let mut p: *mut Point = (*next).next;
-    while (*p).i != (*prev).i {
-      if (x0<=(*p).x) && ((*p).x<=x1) && (y0<=(*p).y) && ((*p).y<=y1) {
-        if is_point_inside(ax,ay, bx,by, cx,cy, (*p).x,(*p).y) {
+    if !repeats {
+      while (*p).i != (*prev).i {
+        if (x0<=(*p).x) && ((*p).x<=x1) && (y0<=(*p).y) && ((*p).y<=y1) && is_point_inside(ax,ay, bx,by, cx,cy, (*p).x,(*p).y) {
           return false;
         }
+        p = (*p).next;
+      }
+      return true;
+    }
+
+    // A flat ear running straight through a repeated point (the end of a bridge) is left:
+    // clipping it would leave the other end on the new edge.
+    let flat = side(ax,ay, bx,by, cx,cy).abs()<=ROUNDING && (ax-bx)*(cx-bx) + (ay-by)*(cy-by) < 0.;
+    while (*p).i != (*prev).i {
+      if (x0<=(*p).x) && ((*p).x<=x1) && (y0<=(*p).y) && ((*p).y<=y1) && (blocks_ear(p, ax,ay, bx,by, cx,cy) || (flat && (*p).x==bx && (*p).y==by)) {
+        return false;
       }
       p = (*p).next;
     }
@@ -109,57 +418,129 @@ fn is_ear(prev: *mut Point, v: *mut Point, next: *mut Point) -> bool {
   }
 }
 
-/* Do ear-clipping and return index list. */
-fn earcut(cycles: &Vec<SimpleCycle>) -> Vec<usize> {
+/* Does the point p make the ear (a, b, c) of a cycle repeating a point invalid? */
+// A point only sits on a corner of the ear when the cycle repeats it, as bridged holes (see polygon.rs) do
+// with both ends of each bridge. Checked by `is_point_inside` alone, such an ear is never clipped
+// and the rest of the cycle is left untriangulated. Cycles without a repeated point keep that check.
+// - On a corner, p blocks the ear only when one of its edges heads between the two edges of that corner,
+//   i.e. into the ear.
+// - Elsewhere, only a point which isn't convex (reflex or flat) can block it, as in mapbox/earcut.js:
+//   if a convex point is inside, the boundary through it enters the ear, and has a reflex or flat point inside too.
+//   The only convex points let through are the ones touching an edge of the ear from outside,
+//   which don't overlap the clipped triangle with the rest of the cycle.
+// - On an edge of the ear, which points found on segments are only up to rounding, p blocks it
+//   like on a corner: when one of its edges heads into the ear.
+fn blocks_ear(p: *mut Point, ax: f64, ay: f64, bx: f64, by: f64, cx: f64, cy: f64) -> bool {
   unsafe {
-    let mut indices: Vec<usize> = Vec::new();
-
-    cycles.iter().for_each(|cycle| {
-      let mut v: *mut Point = cycle.point;
-      let mut prev: *mut Point;
-      let mut next: *mut Point;
-      let mut stopi = (*v).i;
-
-      while (*(*v).prev).i != (*(*v).next).i {
-        prev = (*v).prev;
-        next = (*v).next;
-
-        if is_ear(prev, v, next) {
-          indices.extend(vec![(*prev).i, (*v).i, (*next).i]);
-          (*prev).next = next;
-          (*next).prev = prev;
-          drop(Box::from_raw(v)); // consume
-
-          v = (*next).next;
-          stopi = (*v).i;
-          continue;
-        }
+    let (px, py) = ((*p).x, (*p).y);
+    let corner = if px==ax && py==ay {
+      Some((bx,by, cx,cy))
+    } else if px==bx && py==by {
+      Some((cx,cy, ax,ay))
+    } else if px==cx && py==cy {
+      Some((ax,ay, bx,by))
+    } else {
+      None
+    };
+    match corner {
+      Some((ux,uy, wx,wy)) => [(*p).prev, (*p).next].into_iter().any(|q| {
+        area(px,py, ux,uy, (*q).x,(*q).y)==Winding::CCW && area(px,py, (*q).x,(*q).y, wx,wy)==Winding::CCW
+      }),
+      None => (is_point_inside(ax,ay, bx,by, cx,cy, px,py)
+        && area((*(*p).prev).x, (*(*p).prev).y, px, py, (*(*p).next).x, (*(*p).next).y)!=Winding::CCW)
+        || [(ax,ay, bx,by), (bx,by, cx,cy), (cx,cy, ax,ay)].into_iter().any(|(ux,uy, wx,wy)| {
+          let along = (px-ux)*(wx-ux) + (py-uy)*(wy-uy);
+          side(ux,uy, wx,wy, px,py).abs()<=ROUNDING && along>0. && along<(wx-ux)*(wx-ux) + (wy-uy)*(wy-uy)
+            && [(*p).prev, (*p).next].into_iter().any(|q| side(ux,uy, wx,wy, (*q).x,(*q).y)>ROUNDING)
+        }),
+    }
+  }
+}
 
-        v = (*v).next;
-        if (*v).i==stopi {
-          break;
-        }
+/// Relative error allowed for points found on segments, which are rounded.
+pub(crate) const ROUNDING: f64 = 1e-9;
+
+/* Sine of the angle from u->w to u->q: positive when q is left of u->w. */
+pub(crate) fn side(ux: f64, uy: f64, wx: f64, wy: f64, qx: f64, qy: f64) -> f64 {
+  let cross = (wx-ux)*(qy-uy) - (wy-uy)*(qx-ux);
+  if cross==0. { 0. } else { cross/(f64::hypot(wx-ux, wy-uy)*f64::hypot(qx-ux, qy-uy)) }
+}
+
+/* Triangulate the cycles by the backend and return index list. */
+fn triangulate_cycles(cycles: &Vec<SimpleCycle>, backend: Backend) -> Vec<usize> {
+  let mut indices: Vec<usize> = Vec::new();
+  cycles.iter().for_each(|cycle| {
+    match backend {
+      Backend::EarCut => earcut_cycle(cycle, &mut indices),
+      Backend::Monotone => monotone::triangulate_cycle(cycle, &mut indices),
+    }
+  });
+  indices
+}
+
+/* Does the cycle from v pass twice through a point? */
+fn repeats_point(v: *mut Point) -> bool {
+  unsafe {
+    let mut seen = std::collections::HashSet::new();
+    let mut p = v;
+    loop {
+      if !seen.insert((((*p).x+0.).to_bits(), ((*p).y+0.).to_bits())) {
+        return true;
       }
+      p = (*p).next;
+      if p==v {
+        return false;
+      }
+    }
+  }
+}
+
+/* Ear-clipping of a cycle, consuming its points. */
+pub(crate) fn earcut_cycle(cycle: &SimpleCycle, indices: &mut Vec<usize>) {
+  unsafe {
+    let mut v: *mut Point = cycle.point;
+    let mut prev: *mut Point;
+    let mut next: *mut Point;
+    let mut stopi = (*v).i;
+    let repeats = repeats_point(v);
+
+    while (*(*v).prev).i != (*(*v).next).i {
+      prev = (*v).prev;
+      next = (*v).next;
+
+      if is_ear(prev, v, next, repeats) {
+        indices.extend(vec![(*prev).i, (*v).i, (*next).i]);
+        (*prev).next = next;
+        (*next).prev = prev;
+        drop(Box::from_raw(v)); // consume
+
+        v = (*next).next;
+        stopi = (*v).i;
+        continue;
+      }
+
+      v = (*v).next;
+      if (*v).i==stopi {
+        break;
+      }
+    }
 
-      // consume
-      let i = (*(*v).prev).i;
-      if i == (*v).i {
+    // consume
+    let i = (*(*v).prev).i;
+    if i == (*v).i {
+      drop(Box::from_raw(v));
+    } else {
+      let mut v2: *mut Point;
+      loop {
+        v2 = (*v).next;
         drop(Box::from_raw(v));
-      } else {
-        let mut v2: *mut Point;
-        loop {
-          v2 = (*v).next;
+        v = v2;
+        if (*v).i == i {
           drop(Box::from_raw(v));
-          v = v2;
-          if (*v).i == i {
-            drop(Box::from_raw(v));
-            break;
-          }
+          break;
         }
       }
-    });
-    
-    indices
+    }
   }
 }
 
@@ -194,14 +575,20 @@ fn decomp_simple<'a>(array: &'a Vec<*mut Vertex<'a>>) -> (Vec<f64>, Vec<SimpleCy
   }
 }
 
-/* decompose into simple polygon cycles. (Simple polygon is non-intersecting polygon.) */
-fn decomp_simples<'a>(array: &'a Vec<*mut Vertex<'a>>) -> (Vec<f64>, Vec<SimpleCycle<'a>>) {
+/* decompose into simple polygon cycles. (Simple polygon is non-intersecting polygon.)
+   `keep_all` keeps the cycles made only of Sects whatever their sign.
+   Also returns the winding each cycle was traced in before being made CCW. */
+fn decomp_simples<'a>(array: &'a Vec<*mut Vertex<'a>>, keep_all: bool) -> (Vec<f64>, Vec<SimpleCycle<'a>>, Vec<Winding>) {
   unsafe {
     let mut new_data: Vec<f64> = Vec::new();
     let mut simple_cycles: Vec<SimpleCycle> = Vec::new();
+    let mut windings: Vec<Winding> = Vec::new();
     let mut i: usize = 0;
     let mut v: *mut Vertex;
     let mut s: *mut Sect;
+    // A cycle passes each vertex and sect once; on degenerate input (e.g. spikes running back along themselves)
+    // the links may not lead back to the start, and the walk is given up.
+    let limit = array.len() + array.iter().map(|v| (*(*v)).sects.as_ref().map_or(0, |s| s.len())).sum::<usize>();
 
     for e in 0..(array.len()) {
       v = array[e];
@@ -219,6 +606,9 @@ fn decomp_simples<'a>(array: &'a Vec<*mut Vertex<'a>>) -> (Vec<f64>, Vec<SimpleC
             loop {
               local_data.push((*s).x); local_data.push((*s).y);
               (*(*s).dual).valid = false;
+              if local_data.len()>limit*2 {
+                break;
+              }
 
               if (*(*s).dual).next.is_null() {
                 v = (*(*s).other).next;
@@ -228,10 +618,13 @@ fn decomp_simples<'a>(array: &'a Vec<*mut Vertex<'a>>) -> (Vec<f64>, Vec<SimpleC
               }
             }
           }
-          if (*v).i==vi {
+          if (*v).i==vi || local_data.len()>limit*2 {
             break;
           }   
         }
+        if local_data.len()>limit*2 {
+          continue;
+        }
         // check winding => make ccw linked points
         // We don't need to check the winding validity because we only checked ones starting from the original vertices.
         let len = local_data.len();
@@ -246,6 +639,7 @@ fn decomp_simples<'a>(array: &'a Vec<*mut Vertex<'a>>) -> (Vec<f64>, Vec<SimpleC
             }
             new_data.extend(local_data);
             simple_cycles.push(SimpleCycle{ point: last, len: len/2 });
+            windings.push(Winding::CCW);
           },
           Winding::CW => {
             let mut new_local_data: Vec<f64> = Vec::new();
@@ -258,6 +652,7 @@ fn decomp_simples<'a>(array: &'a Vec<*mut Vertex<'a>>) -> (Vec<f64>, Vec<SimpleC
             }
             new_data.extend(new_local_data);
             simple_cycles.push(SimpleCycle{ point: last, len: len/2 });
+            windings.push(Winding::CW);
           },
         }
       }
@@ -270,6 +665,7 @@ fn decomp_simples<'a>(array: &'a Vec<*mut Vertex<'a>>) -> (Vec<f64>, Vec<SimpleC
     for e in 0..(array.len()) {
       v = array[e];
       let vi = (*v).i;
+      let mut steps = limit;
       loop {
         if (*v).next_sect.is_null() {
           v = (*v).next;
@@ -277,29 +673,43 @@ fn decomp_simples<'a>(array: &'a Vec<*mut Vertex<'a>>) -> (Vec<f64>, Vec<SimpleC
           s = (*v).next_sect;
           loop {
             if (*s).valid {
-              decomp_remain_sects(&mut s, &mut i, &mut new_data, &mut simple_cycles);
+              decomp_remain_sects(&mut s, &mut i, &mut new_data, &mut simple_cycles, &mut windings, keep_all, limit);
             }
-            if (*(*s).dual).next.is_null() {
+            if (*(*s).dual).next.is_null() || steps==0 {
               v = (*(*s).other).next;
               break;
             } else {
               s = (*(*s).dual).next;
+              steps -= 1;
             }
           }
         }
-        if (*v).i==vi {
+        if (*v).i==vi || steps==0 {
           break;
-        }   
+        }
+        steps -= 1;
+      }
+    }
+    // cycles of Sects may only meet other cycles of Sects; when all are kept, look through every Sect.
+    if keep_all {
+      for &v in array.iter() {
+        if let Some(sects) = &(*v).sects {
+          for &s in sects.iter() {
+            if (*s).valid {
+              decomp_remain_sects(&mut s.clone(), &mut i, &mut new_data, &mut simple_cycles, &mut windings, keep_all, limit);
+            }
+          }
+        }
       }
     }
     // -----
 
-    (new_data, simple_cycles)
+    (new_data, simple_cycles, windings)
   }
 }
 
 // decomp check for remaining Sect
-fn decomp_remain_sects(s: &mut *mut Sect, i: &mut usize, new_data: &mut Vec<f64>, simple_cycles: &mut Vec<SimpleCycle>) {
+fn decomp_remain_sects(s: &mut *mut Sect, i: &mut usize, new_data: &mut Vec<f64>, simple_cycles: &mut Vec<SimpleCycle>, windings: &mut Vec<Winding>, keep_all: bool, limit: usize) {
   unsafe {
     let mut s = *s;
     let si = (*s).i;
@@ -311,7 +721,7 @@ fn decomp_remain_sects(s: &mut *mut Sect, i: &mut usize, new_data: &mut Vec<f64>
       local_data.push((*s).y);
       (*s).valid = false;
 
-      if (*s).next.is_null() {
+      if (*s).next.is_null() || local_data.len()>limit*2 {
         success = false;
         break;
       } else {
@@ -328,7 +738,7 @@ fn decomp_remain_sects(s: &mut *mut Sect, i: &mut usize, new_data: &mut Vec<f64>
       // check winding first;
       match signed_area(&local_data, 2) {
         Winding::Zero => {},
-        Winding::CCW => { if (*s).sign {
+        Winding::CCW => { if keep_all || (*s).sign {
           last = ptr::null_mut();
           for e in (0..len).step_by(2) {
             last = Point::new(*i, local_data[e], local_data[e+1], last);
@@ -336,8 +746,9 @@ fn decomp_remain_sects(s: &mut *mut Sect, i: &mut usize, new_data: &mut Vec<f64>
           }
           new_data.extend(local_data);
           simple_cycles.push(SimpleCycle{ point: last, len: len/2 });
+          windings.push(Winding::CCW);
         } },
-        Winding::CW => { if !(*s).sign {
+        Winding::CW => { if keep_all || !(*s).sign {
           let mut new_local_data: Vec<f64> = Vec::new();
           last = ptr::null_mut();
           for e in (0..len).step_by(2).rev() {
@@ -348,6 +759,7 @@ fn decomp_remain_sects(s: &mut *mut Sect, i: &mut usize, new_data: &mut Vec<f64>
           }
           new_data.extend(new_local_data);
           simple_cycles.push(SimpleCycle{ point: last, len: len/2 });
+          windings.push(Winding::CW);
         } },
       }     
     }
@@ -382,10 +794,15 @@ pub fn top_turn(v: &*mut Vertex) -> bool {
 }
 
 pub fn update_sects(v: &*mut Vertex) {
+  // 1) get top vertex's turn
+  update_sects_from(*v, top_turn(v));
+}
+
+/// [`update_sects`] from the vertex `v`, whose sign is given.
+fn update_sects_from(v: *mut Vertex, sign: bool) {
   unsafe {
-    // 1) get top vertex's turn
-    let mut v: *mut Vertex = *v;
-    let mut sign: bool = top_turn(&v);
+    let mut v: *mut Vertex = v;
+    let mut sign: bool = sign;
     let vi = (*v).i;
 
     loop {
@@ -393,12 +810,12 @@ pub fn update_sects(v: &*mut Vertex) {
       match &mut (*v).sects {
         None => {},
         Some(sects) => {
-          // 2) sort Vertex.sects;
-          if (*v).topdown { // in descending order
-            sects.sort_by(|b, a| (&(*(*a))).partial_cmp(&(*(*b))).unwrap());
-          } else { // in ascending order
-            sects.sort_by(|a, b| (&(*(*a))).partial_cmp(&(*(*b))).unwrap());
-          }
+          // 2) sort Vertex.sects along the segment;
+          // (by their position on it: comparing y first misorders them on a nearly horizontal segment, where y is rounded.)
+          let (x0, y0) = ((*v).x, (*v).y);
+          let (dx, dy) = ((*(*v).next).x-x0, (*(*v).next).y-y0);
+          let along = |s: &*mut Sect| ((*(*s)).x-x0)*dx + ((*(*s)).y-y0)*dy;
+          sects.sort_by(|a, b| along(a).partial_cmp(&along(b)).unwrap());
 
           // 3) restruct them to handle redundants;
           // (1) re-gather by uniqueness
@@ -500,6 +917,14 @@ pub fn update_sects(v: &*mut Vertex) {
 
 // ----- step 2. ----- //
 pub fn update_intersect(array: &Vec<*mut Vertex>) -> bool {
+  find_intersections(array, None, false)
+}
+
+/// [`update_intersect`], which also collects the places where two segments only touch at a vertex,
+/// as (vertex i, vertex i, x, y), into `touches`.
+/// With `rings`, the segments come from several rings (see `decompose_rings`): they are adjacent by their links,
+/// and segments meeting at a vertex are never taken as crossing.
+fn find_intersections(array: &Vec<*mut Vertex>, mut touches: Option<&mut Vec<(usize, usize, f64, f64)>>, rings: bool) -> bool {
 
   let len = array.len();
   let mut count: usize = 0;
@@ -511,7 +936,8 @@ pub fn update_intersect(array: &Vec<*mut Vertex>) -> bool {
       for j in (i+1)..len {
         v1 = array[j];
   
-        if !(&*v0).is_adjacent(&*v1, len) {
+        let adjacent = if rings { (*v0).next==v1 || (*v1).next==v0 } else { (&*v0).is_adjacent(&*v1, len) };
+        if !adjacent {
           // don't need to check afterward.
           if (*v0).bottom > (*v1).top {
             break;
@@ -524,7 +950,10 @@ pub fn update_intersect(array: &Vec<*mut Vertex>) -> bool {
               (*v1).x, (*v1).y, (*(*v1).next).x, (*(*v1).next).y,
             ) {
               // --
-              if t==0. {
+              let (n0, n1) = (&*(*v0).next, &*(*v1).next);
+              if rings && (t==0. || u==0. || (*v0).equals(&*v1) || (*v0).equals(n1) || n0.equals(&*v1) || n0.equals(n1)) {
+                // rings of `decompose_rings` meet at vertices without crossing.
+              } else if t==0. {
                 let mut v0prev = (*v0).prev;
                 let v0_nexti = (*(*v0).next).i;
                 let (mut v0_0x, mut v0_0y) = ((*v0prev).x, (*v0prev).y);
@@ -540,11 +969,11 @@ pub fn update_intersect(array: &Vec<*mut Vertex>) -> bool {
                   (v0_0x, v0_0y) = ((*v0prev).x, (*v0prev).y);
                   area1 = area(v0_0x,v0_0y, px,py, v1_0x,v1_0y);
                 }
-                if (*v0prev).i != v0_nexti {
-                  if area1 == area(v0_1x,v0_1y, px,py, v1_1x,v1_1y) {
-                    insert_sect(v0, v1, px, py, len+count);
-                    count += 1;
-                  }
+                if (*v0prev).i != v0_nexti && area1 == area(v0_1x,v0_1y, px,py, v1_1x,v1_1y) {
+                  insert_sect(v0, v1, px, py, len+count);
+                  count += 1;
+                } else if let Some(touches) = touches.as_mut() {
+                  touches.push(((*v0).i, (*v1).i, px, py));
                 }
               } else if u==0. {
                 let mut v1prev = (*v1).prev;
@@ -562,11 +991,11 @@ pub fn update_intersect(array: &Vec<*mut Vertex>) -> bool {
                   (v1_0x, v1_0y) = ((*v1prev).x, (*v1prev).y);
                   area1 = area(v0_0x,v0_0y, px,py, v1_0x,v1_0y);
                 }
-                if (*v1prev).i != v1_nexti {
-                  if area1 == area(v0_1x,v0_1y, px,py, v1_1x,v1_1y) {
-                    insert_sect(v0, v1, px, py, len+count);
-                    count += 1;
-                  }
+                if (*v1prev).i != v1_nexti && area1 == area(v0_1x,v0_1y, px,py, v1_1x,v1_1y) {
+                  insert_sect(v0, v1, px, py, len+count);
+                  count += 1;
+                } else if let Some(touches) = touches.as_mut() {
+                  touches.push(((*v0).i, (*v1).i, px, py));
                 }
               } else {
                 insert_sect(v0, v1, px, py, len+count);
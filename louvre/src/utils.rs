@@ -1,6 +1,6 @@
 /// Indicating winding direction of a vertex list
 /// 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Winding {
   CCW, CW, Zero,
 }
@@ -25,4 +25,21 @@ pub fn signed_area(data: &Vec<f64>, dim: usize) -> Winding {
 pub fn area(ax: f64, ay: f64, bx: f64, by: f64, cx: f64, cy: f64,) -> Winding {
   let result = (by-ay)*(cx-bx) - (bx-ax)*(cy-by);
   if result>0. {Winding::CW} else if result<0. {Winding::CCW} else {Winding::Zero}
-}
\ No newline at end of file
+}
+
+/// Signed area of a polygon as a value; positive for CCW (with the y axis going up), negative for CW.
+/// This is the plain shoelace sum, which is meaningless for self-intersecting polygons;
+/// see [`measure`](crate::measure) for those.
+pub fn signed_area_value(data: &[f64], dim: usize) -> f64 {
+  let len = data.len()/dim;
+  if len<3 {
+    return 0.;
+  }
+  let mut sum = 0.;
+  let mut j = len-1;
+  for i in 0..len {
+    sum += (data[j*dim]-data[i*dim])*(data[j*dim+1]+data[i*dim+1]);
+    j = i;
+  }
+  sum*0.5
+}
@@ -0,0 +1,56 @@
+//! Texture coordinates
+//!
+//! UVs for texture-mapped polygon fills (hatch patterns, imagery...), computed from the x and y of each vertex.
+//! They come as a separate array aligned with `new_data` ([`uvs`]), or interleaved into it ([`interleave_uvs`]).
+
+use crate::*;
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UvMode {
+  /// The bounding box of the vertices maps onto [0, 1] x [0, 1]: the texture is stretched over the polygon once.
+  BoundingBox,
+  /// The texture repeats every (width, height) in world units, from the origin; polygons next to each other line up.
+  World(f64, f64),
+  /// u = a*x + b*y + c, v = d*x + e*y + f, from [a, b, c, d, e, f]; e.g. for rotated or georeferenced imagery.
+  Affine([f64; 6]),
+}
+
+
+/// UVs of the vertices of a coordinates array with `dim`: a array with dim 2, aligned with `new_data`.
+pub fn uvs(new_data: &[f64], dim: usize, mode: UvMode) -> Vec<f64> {
+  let transform: [f64; 6] = match mode {
+    UvMode::BoundingBox => {
+      let (mut x0, mut y0, mut x1, mut y1) = (f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+      for p in new_data.chunks_exact(dim) {
+        x0 = x0.min(p[0]);
+        y0 = y0.min(p[1]);
+        x1 = x1.max(p[0]);
+        y1 = y1.max(p[1]);
+      }
+      // a flat side maps to 0
+      let (sx, sy) = (if x1>x0 { 1./(x1-x0) } else { 0. }, if y1>y0 { 1./(y1-y0) } else { 0. });
+      [sx, 0., -x0*sx, 0., sy, -y0*sy]
+    },
+    UvMode::World(width, height) => [1./width, 0., 0., 0., 1./height, 0.],
+    UvMode::Affine(transform) => transform,
+  };
+  let [a, b, c, d, e, f] = transform;
+  new_data.chunks_exact(dim).flat_map(|p| [a*p[0] + b*p[1] + c, d*p[0] + e*p[1] + f]).collect()
+}
+
+/// Interleaves UVs (dim 2) into a coordinates array with `dim`: each vertex becomes its coordinates followed by u, v,
+/// i.e. a vertex buffer with dim `dim+2`.
+pub fn interleave_uvs(new_data: &[f64], dim: usize, uvs: &[f64]) -> Vec<f64> {
+  new_data.chunks_exact(dim).zip(uvs.chunks_exact(2))
+    .flat_map(|(p, uv)| p.iter().chain(uv.iter()).copied())
+    .collect()
+}
+
+/// Triangulates a polygon (see [`triangulate`]) with UVs.
+/// Returns a vertex buffer of x, y, u, v (dim 4) and a index array of it.
+pub fn triangulate_uv(data: &mut Vec<f64>, dim: usize, mode: UvMode) -> (Vec<f64>, Vec<usize>) {
+  let (new_data, indices) = triangulate(data, dim);
+  let uvs = uvs(&new_data, 2, mode);
+  (interleave_uvs(&new_data, 2, &uvs), indices)
+}
@@ -0,0 +1,442 @@
+//! WKT and WKB input/output
+//!
+//! Parses `POLYGON`/`MULTIPOLYGON` from WKT (and EWKT) or WKB (and EWKB) without any dependency,
+//! and writes a triangulation back as a `TIN` or a `MULTIPOLYGON` of triangles.
+
+use crate::*;
+use crate::polygon::append_mesh;
+use std::fmt;
+
+
+/// Polygons parsed from WKT/WKB.
+/// Each polygon is a list of rings (the outer ring first), and each ring is a coordinates array with `dim`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygons {
+  /// 2, or 3 when the geometry has Z.
+  pub dim: usize,
+  pub polygons: Vec<Vec<Vec<f64>>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WktError {
+  /// Unexpected token or character, with its position.
+  Syntax(usize),
+  UnexpectedEnd,
+  /// Geometry type other than (MULTI)POLYGON.
+  UnsupportedType(String),
+}
+
+impl fmt::Display for WktError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      WktError::Syntax(i) => write!(f, "syntax error at {}", i),
+      WktError::UnexpectedEnd => write!(f, "unexpected end of input"),
+      WktError::UnsupportedType(t) => write!(f, "unsupported geometry type: {}", t),
+    }
+  }
+}
+
+impl std::error::Error for WktError {}
+
+/// Geometry type of the written triangles.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TriangleOutput {
+  Tin,
+  MultiPolygon,
+}
+
+
+impl Polygons {
+  /// Triangulates all polygons and returns a merged mesh (with dim 2).
+  /// Z values are not carried into the mesh: points added at intersections have no Z to take,
+  /// so use [`Polygons::polygons`] for the parsed Z.
+  pub fn triangulate(&self) -> (Vec<f64>, Vec<usize>) {
+    let mut new_data: Vec<f64> = Vec::new();
+    let mut indices: Vec<usize> = Vec::new();
+    for rings in self.polygons.iter() {
+      append_mesh(triangulate_polygon(rings, self.dim), &mut new_data, &mut indices);
+    }
+    (new_data, indices)
+  }
+}
+
+/// Parses WKT `POLYGON`/`MULTIPOLYGON` then triangulates it into a mesh with dim 2; Z values are dropped.
+pub fn triangulate_wkt(s: &str) -> Result<(Vec<f64>, Vec<usize>), WktError> {
+  Ok(parse_wkt(s)?.triangulate())
+}
+
+/// Parses WKB `POLYGON`/`MULTIPOLYGON` then triangulates it into a mesh with dim 2; Z values are dropped.
+pub fn triangulate_wkb(bytes: &[u8]) -> Result<(Vec<f64>, Vec<usize>), WktError> {
+  Ok(parse_wkb(bytes)?.triangulate())
+}
+
+
+// ----- WKT ----- //
+
+/// Parses WKT (or EWKT with `SRID=...;` prefix) `POLYGON` or `MULTIPOLYGON`, with optional Z.
+/// M values are dropped.
+pub fn parse_wkt(s: &str) -> Result<Polygons, WktError> {
+  let mut p = WktParser{ s: s.as_bytes(), i: 0, dim: 0, tag: None };
+
+  // EWKT srid prefix
+  p.skip_ws();
+  if p.s[p.i..].len()>=5 && p.s[p.i..p.i+5].eq_ignore_ascii_case(b"SRID=") {
+    while p.i<p.s.len() && p.s[p.i]!=b';' {
+      p.i += 1;
+    }
+    p.expect(b';')?;
+  }
+
+  let word = p.word()?;
+  p.tag = p.dim_tag()?;
+  if let Some((has_z, _)) = p.tag {
+    p.dim = if has_z {3} else {2};
+  }
+
+  let polygons = match word.as_str() {
+    "POLYGON" => {
+      match p.polygon()? {
+        Some(rings) => vec![rings],
+        None => Vec::new(),
+      }
+    },
+    "MULTIPOLYGON" => {
+      let mut polygons = Vec::new();
+      if !p.empty()? {
+        p.expect(b'(')?;
+        loop {
+          if let Some(rings) = p.polygon()? {
+            polygons.push(rings);
+          }
+          if !p.comma_or_close()? {
+            break;
+          }
+        }
+      }
+      polygons
+    },
+    _ => return Err(WktError::UnsupportedType(word)),
+  };
+  p.skip_ws();
+  if p.i<p.s.len() {
+    return Err(WktError::Syntax(p.i));
+  }
+  Ok(Polygons{ dim: p.dim.max(2), polygons })
+}
+
+struct WktParser<'a> {
+  s: &'a [u8],
+  i: usize,
+  /// output dim; decided by the first coordinate when there is no dim tag.
+  dim: usize,
+  /// (has z, has m) of the dim tag
+  tag: Option<(bool, bool)>,
+}
+
+impl WktParser<'_> {
+  fn skip_ws(&mut self) {
+    while self.i<self.s.len() && self.s[self.i].is_ascii_whitespace() {
+      self.i += 1;
+    }
+  }
+
+  fn peek(&mut self) -> Option<u8> {
+    self.skip_ws();
+    self.s.get(self.i).copied()
+  }
+
+  fn expect(&mut self, c: u8) -> Result<(), WktError> {
+    match self.peek() {
+      None => Err(WktError::UnexpectedEnd),
+      Some(x) if x==c => { self.i += 1; Ok(()) },
+      _ => Err(WktError::Syntax(self.i)),
+    }
+  }
+
+  fn word(&mut self) -> Result<String, WktError> {
+    self.skip_ws();
+    let start = self.i;
+    while self.i<self.s.len() && self.s[self.i].is_ascii_alphabetic() {
+      self.i += 1;
+    }
+    if start==self.i {
+      return if self.i<self.s.len() { Err(WktError::Syntax(self.i)) } else { Err(WktError::UnexpectedEnd) };
+    }
+    Ok(String::from_utf8_lossy(&self.s[start..self.i]).to_ascii_uppercase())
+  }
+
+  /// Optional `Z`, `M` or `ZM` after the type name.
+  fn dim_tag(&mut self) -> Result<Option<(bool, bool)>, WktError> {
+    match self.peek() {
+      Some(c) if c.is_ascii_alphabetic() => {
+        let start = self.i;
+        let word = self.word()?;
+        match word.as_str() {
+          "Z" => Ok(Some((true, false))),
+          "M" => Ok(Some((false, true))),
+          "ZM" => Ok(Some((true, true))),
+          "EMPTY" => { self.i = start; Ok(None) },
+          _ => Err(WktError::Syntax(start)),
+        }
+      },
+      _ => Ok(None),
+    }
+  }
+
+  fn empty(&mut self) -> Result<bool, WktError> {
+    match self.peek() {
+      Some(c) if c.is_ascii_alphabetic() => {
+        let start = self.i;
+        if self.word()?=="EMPTY" { Ok(true) } else { Err(WktError::Syntax(start)) }
+      },
+      _ => Ok(false),
+    }
+  }
+
+  /// `,` => true, `)` => false
+  fn comma_or_close(&mut self) -> Result<bool, WktError> {
+    match self.peek() {
+      None => Err(WktError::UnexpectedEnd),
+      Some(b',') => { self.i += 1; Ok(true) },
+      Some(b')') => { self.i += 1; Ok(false) },
+      _ => Err(WktError::Syntax(self.i)),
+    }
+  }
+
+  fn number(&mut self) -> Result<f64, WktError> {
+    self.skip_ws();
+    let start = self.i;
+    while self.i<self.s.len() && (self.s[self.i].is_ascii_digit() || b"+-.eE".contains(&self.s[self.i])) {
+      self.i += 1;
+    }
+    std::str::from_utf8(&self.s[start..self.i]).ok()
+      .and_then(|x| x.parse::<f64>().ok())
+      .ok_or(if start<self.s.len() { WktError::Syntax(start) } else { WktError::UnexpectedEnd })
+  }
+
+  /// `(x y [z] [m], ...)`
+  fn ring(&mut self) -> Result<Vec<f64>, WktError> {
+    let mut data: Vec<f64> = Vec::new();
+    self.expect(b'(')?;
+    loop {
+      let start = self.i;
+      let mut values: Vec<f64> = Vec::with_capacity(4);
+      while let Some(c) = self.peek() {
+        if c==b',' || c==b')' {
+          break;
+        }
+        values.push(self.number()?);
+      }
+      // Without any dim tag, 3 values mean Z and 4 values mean ZM.
+      let (has_z, has_m) = match (self.tag, values.len()) {
+        (Some(tag), _) => tag,
+        (None, 3) => (true, false),
+        (None, 4) => (true, true),
+        _ => (false, false),
+      };
+      if values.len() != 2 + has_z as usize + has_m as usize {
+        return Err(WktError::Syntax(start));
+      }
+      if self.dim==0 {
+        self.dim = if has_z {3} else {2};
+      }
+      data.push(values[0]);
+      data.push(values[1]);
+      if self.dim==3 {
+        data.push(if has_z { values[2] } else { 0. });
+      }
+      if !self.comma_or_close()? {
+        break;
+      }
+    }
+    Ok(data)
+  }
+
+  /// `((...), (...))` or `EMPTY`
+  fn polygon(&mut self) -> Result<Option<Vec<Vec<f64>>>, WktError> {
+    if self.empty()? {
+      return Ok(None);
+    }
+    let mut rings: Vec<Vec<f64>> = Vec::new();
+    self.expect(b'(')?;
+    loop {
+      rings.push(self.ring()?);
+      if !self.comma_or_close()? {
+        break;
+      }
+    }
+    Ok(Some(rings))
+  }
+}
+
+
+// ----- WKB ----- //
+
+const WKB_POLYGON: u32 = 3;
+const WKB_MULTIPOLYGON: u32 = 6;
+const WKB_TIN: u32 = 16;
+const WKB_TRIANGLE: u32 = 17;
+const EWKB_Z: u32 = 0x80000000;
+const EWKB_M: u32 = 0x40000000;
+const EWKB_SRID: u32 = 0x20000000;
+
+/// Parses WKB (ISO or EWKB, little or big endian) `POLYGON` or `MULTIPOLYGON`, with optional Z.
+/// M values are dropped.
+pub fn parse_wkb(bytes: &[u8]) -> Result<Polygons, WktError> {
+  let mut r = WkbReader{ b: bytes, i: 0, le: true };
+  let (kind, has_z, has_m) = r.header()?;
+  let dim = if has_z {3} else {2};
+  let polygons = match kind {
+    WKB_POLYGON => vec![r.polygon(has_z, has_m)?],
+    WKB_MULTIPOLYGON => {
+      let n = r.u32()?;
+      let mut polygons = Vec::new();
+      for _ in 0..n {
+        let (kind, z, m) = r.header()?;
+        if kind!=WKB_POLYGON || z!=has_z || m!=has_m {
+          return Err(WktError::UnsupportedType(format!("{}", kind)));
+        }
+        polygons.push(r.polygon(has_z, has_m)?);
+      }
+      polygons
+    },
+    _ => return Err(WktError::UnsupportedType(format!("{}", kind))),
+  };
+  Ok(Polygons{ dim, polygons: polygons.into_iter().filter(|x| !x.is_empty()).collect() })
+}
+
+struct WkbReader<'a> {
+  b: &'a [u8],
+  i: usize,
+  le: bool,
+}
+
+impl WkbReader<'_> {
+  fn take<const N: usize>(&mut self) -> Result<[u8; N], WktError> {
+    let bytes = self.b.get(self.i..self.i+N).ok_or(WktError::UnexpectedEnd)?;
+    self.i += N;
+    Ok(bytes.try_into().unwrap())
+  }
+
+  fn u32(&mut self) -> Result<u32, WktError> {
+    let b = self.take::<4>()?;
+    Ok(if self.le { u32::from_le_bytes(b) } else { u32::from_be_bytes(b) })
+  }
+
+  fn f64(&mut self) -> Result<f64, WktError> {
+    let b = self.take::<8>()?;
+    Ok(if self.le { f64::from_le_bytes(b) } else { f64::from_be_bytes(b) })
+  }
+
+  /// byte order + geometry type => (base type, has z, has m)
+  fn header(&mut self) -> Result<(u32, bool, bool), WktError> {
+    let start = self.i;
+    self.le = match self.take::<1>()?[0] {
+      0 => false,
+      1 => true,
+      _ => return Err(WktError::Syntax(start)),
+    };
+    let t = self.u32()?;
+    if t & EWKB_SRID != 0 {
+      self.u32()?;
+    }
+    let mut has_z = t & EWKB_Z != 0;
+    let mut has_m = t & EWKB_M != 0;
+    let t = t & 0x0fffffff;
+    match t/1000 {
+      1 => has_z = true,
+      2 => has_m = true,
+      3 => { has_z = true; has_m = true; },
+      _ => {},
+    }
+    Ok((t%1000, has_z, has_m))
+  }
+
+  fn polygon(&mut self, has_z: bool, has_m: bool) -> Result<Vec<Vec<f64>>, WktError> {
+    let n_rings = self.u32()?;
+    let mut rings: Vec<Vec<f64>> = Vec::new();
+    for _ in 0..n_rings {
+      let n = self.u32()? as usize;
+      // the count comes from the input, so check it against the bytes left before reserving for it.
+      let point_size = (2+has_z as usize+has_m as usize)*8;
+      if n>(self.b.len()-self.i)/point_size {
+        return Err(WktError::UnexpectedEnd);
+      }
+      let mut ring: Vec<f64> = Vec::with_capacity(n*(2+has_z as usize));
+      for _ in 0..n {
+        ring.push(self.f64()?);
+        ring.push(self.f64()?);
+        if has_z {
+          ring.push(self.f64()?);
+        }
+        if has_m {
+          self.f64()?;
+        }
+      }
+      rings.push(ring);
+    }
+    Ok(rings)
+  }
+}
+
+
+// ----- output ----- //
+
+/// Writes triangles as WKT `TIN` or `MULTIPOLYGON`; with Z when `dim` is 3 or more.
+/// Each triangle is written as a closed ring of 4 points.
+pub fn to_wkt(new_data: &[f64], dim: usize, indices: &[usize], output: TriangleOutput) -> String {
+  let z = dim>=3;
+  let name = match output { TriangleOutput::Tin => "TIN", TriangleOutput::MultiPolygon => "MULTIPOLYGON" };
+  let tag = if z { " Z" } else { "" };
+  if indices.len()<3 {
+    return format!("{}{} EMPTY", name, tag);
+  }
+
+  let coord = |i: usize| {
+    if z {
+      format!("{} {} {}", new_data[i*dim], new_data[i*dim+1], new_data[i*dim+2])
+    } else {
+      format!("{} {}", new_data[i*dim], new_data[i*dim+1])
+    }
+  };
+  let triangles: Vec<String> = indices.chunks_exact(3).map(|t| {
+    format!("(({}, {}, {}, {}))", coord(t[0]), coord(t[1]), coord(t[2]), coord(t[0]))
+  }).collect();
+  format!("{}{} ({})", name, tag, triangles.join(", "))
+}
+
+/// Writes triangles as ISO WKB `TIN` or `MULTIPOLYGON`; with Z when `dim` is 3 or more.
+pub fn to_wkb(new_data: &[f64], dim: usize, indices: &[usize], output: TriangleOutput, little_endian: bool) -> Vec<u8> {
+  let z = dim>=3;
+  let zt = if z { 1000 } else { 0 };
+  let (kind, child) = match output {
+    TriangleOutput::Tin => (WKB_TIN, WKB_TRIANGLE),
+    TriangleOutput::MultiPolygon => (WKB_MULTIPOLYGON, WKB_POLYGON),
+  };
+
+  let mut b: Vec<u8> = Vec::new();
+  let put_u32 = |b: &mut Vec<u8>, x: u32| {
+    b.extend(if little_endian { x.to_le_bytes() } else { x.to_be_bytes() });
+  };
+  let put_f64 = |b: &mut Vec<u8>, x: f64| {
+    b.extend(if little_endian { x.to_le_bytes() } else { x.to_be_bytes() });
+  };
+
+  b.push(little_endian as u8);
+  put_u32(&mut b, kind+zt);
+  put_u32(&mut b, (indices.len()/3) as u32);
+  for t in indices.chunks_exact(3) {
+    b.push(little_endian as u8);
+    put_u32(&mut b, child+zt);
+    put_u32(&mut b, 1); // one ring
+    put_u32(&mut b, 4); // closed
+    for i in [t[0], t[1], t[2], t[0]] {
+      put_f64(&mut b, new_data[i*dim]);
+      put_f64(&mut b, new_data[i*dim+1]);
+      if z {
+        put_f64(&mut b, new_data[i*dim+2]);
+      }
+    }
+  }
+  b
+}
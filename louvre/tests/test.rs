@@ -231,4 +231,1174 @@ fn test_triangulate() {
     ]
   );
   assert_eq!(indices, vec![1, 2, 0, 4, 5, 3, 7, 8, 6]);
-}
\ No newline at end of file
+}
+
+fn mesh_area(new_data: &Vec<f64>, indices: &Vec<usize>) -> f64 {
+  indices.chunks(3).map(|t| {
+    let (a, b, c) = (t[0]*2, t[1]*2, t[2]*2);
+    ((new_data[b]-new_data[a])*(new_data[c+1]-new_data[a+1]) - (new_data[c]-new_data[a])*(new_data[b+1]-new_data[a+1])) * 0.5
+  }).sum()
+}
+
+/// Signed area of rings with dim 2, summed; holes (CW) count negative.
+fn rings_area(rings: &[Vec<f64>]) -> f64 {
+  rings.iter().map(|r| louvre::utils::signed_area_value(r, 2)).sum()
+}
+
+/// The first ring of an asset file, as a coordinates array with dim 2.
+fn asset_ring(json: &str) -> Vec<f64> {
+  json[..json.find("]]").unwrap()]
+    .split(|c: char| !(c.is_ascii_digit() || c=='-' || c=='.'))
+    .filter(|t| !t.is_empty())
+    .map(|t| t.parse().unwrap())
+    .collect()
+}
+
+/// FNV-1a hash of a triangulation, to compare it exactly with a recorded one.
+fn mesh_hash(new_data: &Vec<f64>, indices: &Vec<usize>) -> u64 {
+  new_data.iter().map(|x| x.to_bits()).chain(indices.iter().map(|&i| i as u64))
+    .fold(0xcbf29ce484222325, |h, w| (h ^ w).wrapping_mul(0x100000001b3))
+}
+
+#[test]
+fn test_earcut_unchanged() {
+  // points, triangles and hash of the output from before holes were bridged, i.e. before the ear check handled repeated points.
+  let cases: Vec<(Vec<f64>, usize, usize, u64)> = vec![
+    (asset_ring(include_str!("../../assets/hilbert.json")), 1026, 1024, 0x900abce7c43631dd),
+    (asset_ring(include_str!("../../assets/water2.json")), 1005, 1003, 0xcec35c115b697c57),
+    (asset_ring(include_str!("../../assets/inter1.json")), 8, 6, 0x4e98f594d75f789b),
+    (asset_ring(include_str!("../../assets/inter2.json")), 7, 5, 0x74fdbe26b548d35c),
+    (asset_ring(include_str!("../../assets/inter3.json")), 9, 3, 0x331b9e1a0f92549d),
+    (asset_ring(include_str!("../../assets/inter4.json")), 8, 4, 0x5dbeebe6cdc3a1be),
+    (vec![[0., 0.], [0., 3.], [3., 0.], [3., 4.], [-1., 0.]].concat(), 9, 3, 0x29779e1a0f92549d),
+  ];
+  for (mut data, points, triangles, hash) in cases {
+    let (new_data, indices) = triangulate(&mut data, 2);
+    assert_eq!((new_data.len()/2, indices.len()/3), (points, triangles));
+    assert_eq!(mesh_hash(&new_data, &indices), hash);
+  }
+}
+
+#[test]
+fn test_triangulate_polygon() {
+  // outer ring closed as in GeoJSON; holes in the same winding as the outer one.
+  let rings = vec![
+    vec![0.,0., 10.,0., 10.,10., 0.,10., 0.,0.],
+    vec![2.,2., 4.,2., 4.,4., 2.,4.],
+    vec![6.,6., 8.,6., 8.,8., 6.,8., 6.,6.],
+  ];
+  let (new_data, indices) = triangulate_polygon(&rings, 2);
+  assert_eq!(mesh_area(&new_data, &indices), 92.);
+
+  let (new_data, indices) = triangulate_polygon(&rings[..1], 2);
+  assert_eq!(mesh_area(&new_data, &indices), 100.);
+
+  // a self-intersecting outer ring fills both lobes, as `triangulate` does, and the hole is cut out of one
+  let bowtie = vec![vec![0.,0., 4.,4., 4.,0., 0.,4.], vec![0.5,1.75, 1.,1.75, 1.,2.25, 0.5,2.25]];
+  let (new_data, indices) = triangulate_polygon(&bowtie, 2);
+  assert_eq!(mesh_area(&new_data, &indices), 7.75);
+}
+
+#[cfg(feature="geojson")]
+#[test]
+fn test_triangulate_geojson() {
+  use louvre::geojson::*;
+
+  let s = r#"{"type": "FeatureCollection", "features": [
+    {"type": "Feature", "properties": {"name": "a"}, "geometry": {"type": "Point", "coordinates": [0, 0]}},
+    {"type": "Feature", "id": 7, "properties": {"name": "b"}, "geometry": {"type": "MultiPolygon", "coordinates": [
+      [[[0,0],[10,0],[10,10],[0,10],[0,0]], [[2,2],[2,8],[8,8],[8,2],[2,2]]],
+      [[[20,0],[22,0],[22,2],[20,2],[20,0]]]
+    ]}}
+  ]}"#;
+  let meshes = triangulate_geojson_str(s).unwrap();
+  assert_eq!(meshes.len(), 1);
+  assert_eq!(meshes[0].index, 1);
+  assert_eq!(meshes[0].id, Some(serde_json::json!(7)));
+  assert_eq!(meshes[0].properties.as_ref().unwrap()["name"], "b");
+  assert_eq!(mesh_area(&meshes[0].new_data, &meshes[0].indices), 68.);
+
+  assert!(triangulate_geojson_str(r#"{"type": "Polygon"}"#).is_err());
+}
+
+#[cfg(feature="geo-types")]
+#[test]
+fn test_geo_types() {
+  use louvre::geo::Triangulate;
+  use geo_types::{polygon, MultiPolygon, LineString};
+
+  let poly = polygon!(
+    exterior: [(x: 0., y: 0.), (x: 10., y: 0.), (x: 10., y: 10.), (x: 0., y: 10.)],
+    interiors: [[(x: 2., y: 2.), (x: 8., y: 2.), (x: 8., y: 8.), (x: 2., y: 8.)]],
+  );
+  let (new_data, indices) = poly.louvre_mesh();
+  assert_eq!(mesh_area(&new_data, &indices), 64.);
+  let triangles = poly.louvre_triangles();
+  assert_eq!(triangles.len(), indices.len()/3);
+  assert_eq!(triangles[0].0.x, new_data[indices[0]*2]);
+
+  let multi = MultiPolygon::new(vec![poly.clone(), poly]);
+  assert_eq!(multi.louvre_triangles().len(), triangles.len()*2);
+
+  let line: LineString<f64> = vec![(0., 0.), (0., 3.), (3., 0.), (3., 4.), (-1., 0.), (0., 0.)].into();
+  assert_eq!(line.louvre_mesh(), triangulate(&mut vec![0., 0., 0., 3., 3., 0., 3., 4., -1., 0.], 2));
+}
+
+#[test]
+fn test_wkt() {
+  use louvre::wkt::*;
+
+  let polygons = parse_wkt("SRID=4326;MULTIPOLYGON Z (((0 0 1, 10 0 1, 10 10 1, 0 10 1, 0 0 1), (2 2 1, 8 2 1, 8 8 1, 2 8 1, 2 2 1)), EMPTY)").unwrap();
+  assert_eq!(polygons.dim, 3);
+  assert_eq!(polygons.polygons.len(), 1);
+  assert_eq!(polygons.polygons[0][1].len(), 15);
+  let (new_data, indices) = polygons.triangulate();
+  assert_eq!(mesh_area(&new_data, &indices), 64.);
+
+  let polygons = parse_wkt("polygon ((0 0, 3 0, 0 3))").unwrap();
+  assert_eq!(polygons, Polygons{ dim: 2, polygons: vec![vec![vec![0., 0., 3., 0., 0., 3.]]] });
+  assert_eq!(parse_wkt("POINT (0 0)"), Err(WktError::UnsupportedType("POINT".to_string())));
+  assert_eq!(parse_wkt("POLYGON ((0 0, 1 0"), Err(WktError::UnexpectedEnd));
+
+  // written triangles can be read again.
+  let (new_data, indices) = triangulate_wkt("POLYGON ((0 0, 4 0, 4 4, 0 4, 0 0))").unwrap();
+  let wkt = to_wkt(&new_data, 2, &indices, TriangleOutput::MultiPolygon);
+  assert!(wkt.starts_with("MULTIPOLYGON (((") && wkt.ends_with(")))"));
+  let (new_data2, indices2) = triangulate_wkt(&wkt).unwrap();
+  assert_eq!(mesh_area(&new_data2, &indices2), 16.);
+  assert_eq!(to_wkt(&[], 2, &[], TriangleOutput::Tin), "TIN EMPTY");
+
+  for le in [true, false] {
+    let wkb = to_wkb(&new_data, 2, &indices, TriangleOutput::MultiPolygon, le);
+    let polygons = parse_wkb(&wkb).unwrap();
+    assert_eq!(polygons.polygons.len(), indices.len()/3);
+    assert_eq!(polygons.polygons[0][0].len(), 8);
+    let (new_data2, indices2) = triangulate_wkb(&wkb).unwrap();
+    assert_eq!(mesh_area(&new_data2, &indices2), 16.);
+
+    let wkb = to_wkb(&[0.,0.,5., 1.,0.,5., 0.,1.,5.], 3, &[0, 1, 2], TriangleOutput::Tin, le);
+    assert_eq!(wkb.len(), 1+4+4 + 1+4+4+4 + 4*3*8);
+    assert_eq!(wkb[0], le as u8);
+  }
+  assert_eq!(parse_wkb(&[1, 3, 0, 0]), Err(WktError::UnexpectedEnd));
+  // a ring claiming u32::MAX points in a few bytes
+  let mut wkb = vec![1, 3, 0, 0, 0, 1, 0, 0, 0, 255, 255, 255, 255];
+  wkb.extend([0; 16]);
+  assert_eq!(parse_wkb(&wkb), Err(WktError::UnexpectedEnd));
+  // Z is parsed, but not carried into the mesh
+  let polygons = parse_wkt("POLYGON Z ((0 0 1, 4 0 2, 4 4 3, 0 0 1))").unwrap();
+  assert_eq!((polygons.dim, polygons.polygons[0][0][5]), (3, 2.));
+  assert_eq!(polygons.triangulate().0.len(), 6);
+}
+
+#[test]
+fn test_fill_rule() {
+  use louvre::fill::*;
+
+  // two overlapping squares
+  let rings = vec![
+    vec![0.,0., 4.,0., 4.,4., 0.,4.],
+    vec![2.,2., 6.,2., 6.,6., 2.,6.],
+  ];
+  let (new_data, indices) = triangulate_fill(&rings, 2, FillRule::NonZero);
+  assert_eq!(mesh_area(&new_data, &indices), 28.);
+  let (new_data, indices) = triangulate_fill(&rings, 2, FillRule::EvenOdd);
+  assert_eq!(mesh_area(&new_data, &indices), 24.);
+  assert_eq!(resolve_rings(&rings, 2, FillRule::EvenOdd).len(), 2);
+
+  // pentagram; the center has winding number 2.
+  let star: Vec<f64> = (0..5).flat_map(|i| {
+    let a = std::f64::consts::PI*0.5 + (i*2) as f64 * std::f64::consts::PI*2./5.;
+    [a.cos(), a.sin()]
+  }).collect();
+  let (d1, i1) = triangulate_fill(&[star.clone()], 2, FillRule::NonZero);
+  let (d2, i2) = triangulate_fill(&[star.clone()], 2, FillRule::EvenOdd);
+  let (d3, i3) = triangulate(&mut star.clone(), 2);
+  assert!((mesh_area(&d1, &i1) - mesh_area(&d3, &i3)).abs() < 1e-9);
+  assert!(mesh_area(&d2, &i2) < mesh_area(&d1, &i1));
+  assert_eq!(resolve_polygons(&[star], 2, FillRule::EvenOdd).len(), 5);
+
+  assert_eq!("evenodd".parse::<FillRule>(), Ok(FillRule::EvenOdd));
+}
+
+#[test]
+fn test_svg_path() {
+  use louvre::svg::*;
+  use louvre::fill::FillRule;
+
+  let d = "M0 0 H10 V10 H0 Z m2,2 h6 v6 h-6 z";
+  let (new_data, indices) = triangulate_svg_path(d, 0.1, FillRule::NonZero).unwrap();
+  assert_eq!(mesh_area(&new_data, &indices), 100.);
+  let (new_data, indices) = triangulate_svg_path(d, 0.1, FillRule::EvenOdd).unwrap();
+  assert_eq!(mesh_area(&new_data, &indices), 64.);
+
+  let subpaths = parse_svg_path(d, 0.1).unwrap();
+  assert_eq!(subpaths[1], vec![2.,2., 8.,2., 8.,8., 2.,8.]);
+
+  // circle of two arcs; flags without separators
+  let subpaths = parse_svg_path("M0 0a5 5 0 1011 0A5 5 0 1 0 0 0z", 0.01).unwrap();
+  assert_eq!(subpaths.len(), 1);
+  let (new_data, indices) = triangulate_svg_path("M0 0a5 5 0 1010 0A5 5 0 1 0 0 0z", 0.001, FillRule::NonZero).unwrap();
+  let area = mesh_area(&new_data, &indices);
+  assert!(area < 25.*std::f64::consts::PI && area > 25.*std::f64::consts::PI - 0.1);
+
+  // curves end at their end points; relative and reflected controls
+  let subpaths = parse_svg_path("M0,0 q5,5 10,0 t10,0 c0-5 -20-5 -20,0 s-5,5 0,5 L0-1e1", 0.5).unwrap();
+  let path = &subpaths[0];
+  assert_eq!(&path[path.len()-2..], &[0., -10.]);
+  assert!(path.windows(2).step_by(2).any(|p| p == [20., 0.]));
+
+  assert_eq!(parse_svg_path("L0 0", 0.1), Err(SvgError::MissingMoveTo));
+  assert_eq!(parse_svg_path("M0 0 L1", 0.1), Err(SvgError::Syntax(7)));
+}
+
+#[test]
+fn test_render_svg() {
+  use louvre::render::*;
+
+  // a bow tie with one intersection at (1,1)
+  let data: Vec<f64> = vec![0.,0., 2.,2., 2.,0., 0.,2.];
+  assert_eq!(intersection_points(&mut data.clone(), 2), vec![1., 1.]);
+
+  let (new_data, indices) = triangulate(&mut data.clone(), 2);
+  let options = SvgOptions { labels: true, colors: Some(vec!["red".to_string()]), ..Default::default() };
+  let svg = render_svg(&data, 2, &new_data, &indices, &options);
+  assert!(svg.starts_with("<svg") && svg.ends_with("</svg>\n"));
+  assert_eq!(svg.matches("<polygon").count(), indices.len()/3 + 1);
+  assert_eq!(svg.matches(r#"fill="red""#).count(), indices.len()/3 + 1);
+  assert_eq!(svg.matches("<circle").count(), 1);
+  assert_eq!(svg.matches("<text").count(), indices.len()/3);
+
+  let svg = render_triangulation(&data, 2, &SvgOptions { intersections: false, ..Default::default() });
+  assert_eq!(svg.matches("<circle").count(), 0);
+
+  // colours are escaped in the attribute
+  let options = SvgOptions { colors: Some(vec![r#"red"/><script/><g a="&"#.to_string()]), ..Default::default() };
+  let svg = render_svg(&data, 2, &new_data, &indices, &options);
+  assert!(!svg.contains("<script") && svg.contains(r#"fill="red&quot;/&gt;&lt;script/&gt;&lt;g a=&quot;&amp;""#));
+}
+
+#[test]
+fn test_export() {
+  use louvre::export::*;
+
+  let mut data: Vec<f64> = vec![0.,0., 4.,0., 4.,4., 2.,1., 0.,4.];
+  let (new_data, indices) = triangulate(&mut data, 2);
+  let lifted: Vec<f64> = new_data.chunks(2).flat_map(|p| [p[0], p[1], 0.]).collect();
+
+  let mut obj: Vec<u8> = Vec::new();
+  write_obj(&mut obj, &new_data, 2, &indices).unwrap();
+  assert_eq!(read_obj(&obj).unwrap(), (lifted.clone(), indices.clone()));
+
+  for encoding in [Encoding::Ascii, Encoding::Binary] {
+    let mut ply: Vec<u8> = Vec::new();
+    write_ply(&mut ply, &new_data, 2, &indices, encoding).unwrap();
+    assert_eq!(read_ply(&ply).unwrap(), (lifted.clone(), indices.clone()));
+
+    // STL has no shared vertices; compare triangles by coordinates.
+    let mut stl: Vec<u8> = Vec::new();
+    write_stl(&mut stl, &new_data, 2, &indices, encoding).unwrap();
+    let (positions, faces) = read_stl(&stl).unwrap();
+    assert_eq!(faces.len(), indices.len());
+    for (&a, &b) in faces.iter().zip(indices.iter()) {
+      assert_eq!(&positions[a*3..a*3+3], &lifted[b*3..b*3+3]);
+    }
+  }
+
+  // z of dim 3 is carried; extra components are dropped.
+  let mesh: Vec<f64> = vec![0.,0.,1.,9., 1.,0.,2.,9., 0.,1.,3.,9.];
+  let mut ply: Vec<u8> = Vec::new();
+  write_ply(&mut ply, &mesh, 4, &[0, 1, 2], Encoding::Binary).unwrap();
+  assert_eq!(read_ply(&ply).unwrap(), (vec![0.,0.,1., 1.,0.,2., 0.,1.,3.], vec![0, 1, 2]));
+
+  // counts beyond the input and indices beyond u32 are errors
+  let header = "ply\nformat binary_little_endian 1.0\nelement vertex 4000000000\nproperty double x\nend_header\n";
+  assert!(read_ply(header.as_bytes()).is_err());
+  let mut face = b"ply\nformat binary_little_endian 1.0\nelement face 1\nproperty list uint uint vertex_indices\nend_header\n".to_vec();
+  face.extend(u32::MAX.to_le_bytes());
+  assert!(read_ply(&face).is_err());
+  assert!(write_ply(&mut Vec::new(), &mesh, 4, &[0, 1, u32::MAX as usize + 1], Encoding::Binary).is_err());
+
+  // quads are split into fans
+  assert_eq!(read_obj(b"v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1/1 2/2 3/3 -1\n").unwrap().1, vec![0, 1, 2, 0, 2, 3]);
+  assert!(read_obj(b"v 0 0 0\nf 1 2 3\n").is_err());
+}
+
+#[cfg(feature="gltf")]
+#[test]
+fn test_gltf() {
+  use louvre::gltf::*;
+
+  let mut data: Vec<f64> = vec![0.,0., 4.,0., 4.,4., 2.,1., 0.,4.];
+  let (new_data, indices) = triangulate(&mut data, 2);
+  let big: Vec<f64> = (0..300).flat_map(|i| [i as f64, (i%2) as f64, 1.]).collect();
+  let big_indices: Vec<usize> = (0..298).flat_map(|i| [i, i+1, i+2]).collect();
+  let meshes = [
+    GltfMesh::new(&new_data, 2, &indices).with_name("footprint"),
+    GltfMesh::new(&big, 3, &big_indices),
+    GltfMesh::new(&[], 2, &[]),
+  ];
+
+  let (json, bin) = to_gltf(&meshes, Some("mesh.bin"));
+  assert!(json.contains(r#""uri":"mesh.bin""#));
+  assert!(json.contains(r#""name":"footprint""#));
+  assert!(json.contains(r#""componentType":5121"#)); // u8 indices
+  assert!(json.contains(r#""componentType":5123"#)); // u16 indices
+  assert!(json.contains(r#""max":[299.0,1.0,1.0]"#));
+  assert_eq!(json.matches(r#""mode":4"#).count(), 2);
+  let len = new_data.len()/2*12 + indices.len();
+  let len = (len+3)/4*4 + big.len()*4 + big_indices.len()*2;
+  assert_eq!(bin.len(), (len+3)/4*4);
+
+  let glb = to_glb(&meshes);
+  assert_eq!(&glb[0..4], b"glTF");
+  assert_eq!(u32::from_le_bytes([glb[8], glb[9], glb[10], glb[11]]) as usize, glb.len());
+  let json_len = u32::from_le_bytes([glb[12], glb[13], glb[14], glb[15]]) as usize;
+  assert_eq!(&glb[16..20], b"JSON");
+  assert_eq!(&glb[20+json_len+4..20+json_len+8], b"BIN\0");
+  assert_eq!(&glb[20+json_len+8..], &bin[..]);
+
+  // the buffer's path is written as a uri; nothing to draw leaves out the scene
+  assert!(to_gltf(&meshes, Some("my mesh#1.bin")).0.contains(r#""uri":"my%20mesh%231.bin""#));
+  let (json, bin) = to_gltf(&[GltfMesh::new(&[], 2, &[])], None);
+  assert!(bin.is_empty() && !json.contains("scene") && !json.contains("nodes") && !json.contains("meshes"));
+}
+
+#[cfg(feature="gltf")]
+#[test]
+fn test_gltf_index_types() {
+  use louvre::gltf::*;
+
+  // a strip over `count` vertices, whose largest index is count-1
+  let component_type = |count: usize| {
+    let positions: Vec<f64> = (0..count).flat_map(|i| [i as f64, (i%2) as f64]).collect();
+    let indices: Vec<usize> = (0..count-2).flat_map(|i| [i, i+1, i+2]).collect();
+    let (json, _) = to_gltf(&[GltfMesh::new(&positions, 2, &indices)], None);
+    [5121, 5123, 5125].into_iter().find(|t| json.contains(&format!(r#""componentType":{}"#, t))).unwrap()
+  };
+  // 255 and 65535 are primitive restart values
+  assert_eq!(component_type(255), 5121);
+  assert_eq!(component_type(256), 5123);
+  assert_eq!(component_type(65535), 5123);
+  assert_eq!(component_type(65536), 5125);
+}
+
+#[test]
+fn test_extrude() {
+  use louvre::extrude::*;
+  use std::collections::HashMap;
+
+  // welded mesh must be closed, and its signed volume = area * height (i.e. normals point outward).
+  let check = |e: &Extrusion, volume: f64| {
+    let (positions, indices) = e.welded();
+    let mut edges: HashMap<(usize, usize), i32> = HashMap::new();
+    for t in indices.chunks(3) {
+      for k in 0..3 {
+        *edges.entry((t[k], t[(k+1)%3])).or_insert(0) += 1;
+      }
+    }
+    assert!(edges.iter().all(|(&(a, b), &n)| edges.get(&(b, a))==Some(&n)));
+    let p = |i: usize| [positions[i*3], positions[i*3+1], positions[i*3+2]];
+    let v: f64 = indices.chunks(3).map(|t| {
+      let (a, b, c) = (p(t[0]), p(t[1]), p(t[2]));
+      a[0]*(b[1]*c[2]-b[2]*c[1]) - a[1]*(b[0]*c[2]-b[2]*c[0]) + a[2]*(b[0]*c[1]-b[1]*c[0])
+    }).sum::<f64>()/6.;
+    assert!((v-volume).abs()<1e-9, "{} {}", v, volume);
+    assert_eq!(e.normals.len(), e.positions.len());
+  };
+
+  let e = extrude(&mut vec![0.,0., 0.,4., 4.,4., 4.,0.], 2, 1., 3.);
+  check(&e, 32.);
+  assert_eq!(e.indices.len()/3, 2*2 + 4*2);
+
+  // bow tie: two triangles touching at an intersection point
+  check(&extrude(&mut vec![0.,0., 2.,2., 2.,0., 0.,2.], 2, 0., 1.), 2.);
+  // with a hole
+  let rings = vec![vec![0.,0., 10.,0., 10.,10., 0.,10.], vec![2.,2., 2.,8., 8.,8., 8.,2.]];
+  check(&extrude_polygon(&rings, 2, -1., 1.), 128.);
+  // self-intersecting
+  let mut data: Vec<f64> = vec![0.,0., 4.,0., 4.,4., 1.,-2., 1.,4.];
+  let (new_data, indices) = triangulate(&mut data.clone(), 2);
+  check(&extrude(&mut data, 2, 0., 2.), mesh_area(&new_data, &indices)*2.);
+}
+
+#[test]
+fn test_triangulate_3d() {
+  use louvre::planar::newell_normal;
+
+  // triangle normals (area-weighted) of a 3d mesh
+  let normal_sum = |new_data: &[f64], indices: &[usize]| {
+    let p = |i: usize| [new_data[i*3], new_data[i*3+1], new_data[i*3+2]];
+    let mut n = [0.; 3];
+    for t in indices.chunks(3) {
+      let (a, b, c) = (p(t[0]), p(t[1]), p(t[2]));
+      let (u, v) = ([b[0]-a[0], b[1]-a[1], b[2]-a[2]], [c[0]-a[0], c[1]-a[1], c[2]-a[2]]);
+      n[0] += (u[1]*v[2]-u[2]*v[1])/2.;
+      n[1] += (u[2]*v[0]-u[0]*v[2])/2.;
+      n[2] += (u[0]*v[1]-u[1]*v[0])/2.;
+    }
+    n
+  };
+
+  // a wall on x=2, which 2d triangulation collapses
+  let wall: Vec<f64> = vec![2.,0.,0., 2.,4.,0., 2.,4.,3., 2.,0.,3.];
+  assert_eq!(newell_normal(&wall, 3), [24., 0., 0.]);
+  let (new_data, indices) = triangulate_3d(&wall, 3);
+  assert_eq!(indices.len(), 6);
+  assert!(new_data.chunks(3).all(|p| p[0]==2.));
+  assert_eq!(normal_sum(&new_data, &indices), [12., 0., 0.]);
+
+  // reversed winding faces the other way
+  let reversed: Vec<f64> = wall.chunks(3).rev().flatten().copied().collect();
+  let (new_data, indices) = triangulate_3d(&reversed, 3);
+  assert_eq!(normal_sum(&new_data, &indices), [-12., 0., 0.]);
+
+  // a tilted bow tie (plane z = x + y) with an extra component; the intersection point is lifted onto the plane
+  let data: Vec<f64> = [[0.,0.], [2.,2.], [2.,0.], [0.,2.]].iter().flat_map(|p| [p[0], p[1], p[0]+p[1], 7.]).collect();
+  let (new_data, indices) = triangulate_3d(&data, 4);
+  assert_eq!(indices.len(), 6);
+  assert!(new_data.chunks(3).all(|p| (p[2]-p[0]-p[1]).abs()<1e-12));
+  assert!(new_data.chunks(3).any(|p| p==[1., 1., 2.]));
+
+  assert_eq!(triangulate_3d(&wall, 2), (vec![], vec![]));
+  assert_eq!(triangulate_3d(&[0.,0.,0., 1.,1.,1., 2.,2.,2.], 3), (vec![], vec![]));
+}
+
+#[test]
+fn test_stroke() {
+  use louvre::stroke::*;
+  use std::f64::consts::PI;
+
+  let area = |(new_data, indices): (Vec<f64>, Vec<usize>)| mesh_area(&new_data, &indices);
+  let style = |join, cap| StrokeStyle { width: 2., join, cap, ..Default::default() };
+  let corner: Vec<f64> = vec![0.,0., 10.,0., 10.,10.];
+
+  assert!((area(stroke_polyline(&corner, 2, &style(LineJoin::Miter, LineCap::Butt))) - 40.).abs()<1e-9);
+  assert!((area(stroke_polyline(&corner, 2, &style(LineJoin::Bevel, LineCap::Butt))) - 39.5).abs()<1e-9);
+  let round = area(stroke_polyline(&corner, 2, &style(LineJoin::Round, LineCap::Butt)));
+  assert!(round < 39.+PI/4. && round > 39.+PI/4.-0.01);
+  assert!((area(stroke_polyline(&corner, 2, &style(LineJoin::Miter, LineCap::Square))) - 44.).abs()<1e-9);
+  let round = area(stroke_polyline(&corner, 2, &style(LineJoin::Miter, LineCap::Round)));
+  assert!(round < 40.+PI && round > 40.+PI-0.05);
+
+  // a sharp turn beyond the miter limit is beveled
+  let sharp: Vec<f64> = vec![0.,0., 10.,0., 0.,1.];
+  let limited = StrokeStyle { miter_limit: 2., ..style(LineJoin::Miter, LineCap::Butt) };
+  assert_eq!(area(stroke_polyline(&sharp, 2, &limited)), area(stroke_polyline(&sharp, 2, &style(LineJoin::Bevel, LineCap::Butt))));
+
+  // going back over itself covers the area only once
+  assert!((area(stroke_polyline(&[0.,0., 10.,0., 5.,0.], 2, &style(LineJoin::Bevel, LineCap::Butt))) - 20.).abs()<1e-9);
+
+  // crossing itself, the overlaps are merged too
+  let crossing: Vec<f64> = vec![-5.,0., 5.,0., 5.,5., 0.,5., 0.,-5.];
+  assert!((area(stroke_polyline(&crossing, 2, &style(LineJoin::Miter, LineCap::Butt))) - 56.).abs()<1e-9);
+
+  // a square ring leaves its inside empty
+  let ring: Vec<f64> = vec![0.,0., 10.,0., 10.,10., 0.,10.];
+  assert!((area(stroke_ring(&ring, 2, &style(LineJoin::Miter, LineCap::Butt))) - 80.).abs()<1e-9);
+  assert!((area(stroke_ring(&ring, 2, &style(LineJoin::Bevel, LineCap::Round))) - 78.).abs()<1e-9);
+}
+
+#[test]
+fn test_offset() {
+  use louvre::offset::*;
+  use std::f64::consts::PI;
+
+  let square: Vec<f64> = vec![0.,0., 0.,10., 10.,10., 10.,0.];
+
+  assert!((rings_area(&offset(&square, 2, 1., JoinStyle::Miter(2.))) - 144.).abs()<1e-9);
+  assert!((rings_area(&offset(&square, 2, 1., JoinStyle::Bevel)) - 142.).abs()<1e-9);
+  // a limit below sqrt(2) bevels the right angles
+  assert!((rings_area(&offset(&square, 2, 1., JoinStyle::Miter(1.4))) - 142.).abs()<1e-9);
+  let round = rings_area(&offset(&square, 2, 1., JoinStyle::Round(0.01)));
+  assert!(round < 140.+PI && round > 140.+PI-0.05);
+
+  assert!((rings_area(&offset(&square, 2, -1., JoinStyle::Miter(2.))) - 64.).abs()<1e-9);
+  assert!(offset(&square, 2, -6., JoinStyle::Round(0.01)).is_empty());
+
+  // concave corner of an L shape: only the convex corners get joins
+  let l: Vec<f64> = vec![0.,0., 4.,0., 4.,2., 2.,2., 2.,4., 0.,4.];
+  let rings = offset(&l, 2, 1., JoinStyle::Miter(2.));
+  assert_eq!(rings.len(), 1);
+  assert!((rings_area(&rings) - 32.).abs()<1e-9);
+
+  // an inward offset may split a polygon
+  let dumbbell: Vec<f64> = vec![0.,0., 4.,0., 4.,1.5, 6.,1.5, 6.,0., 10.,0., 10.,4., 6.,4., 6.,2.5, 4.,2.5, 4.,4., 0.,4.];
+  assert_eq!(offset(&dumbbell, 2, -1., JoinStyle::Miter(2.)).len(), 2);
+
+  // holes shrink when the polygon grows
+  let rings = vec![square.clone(), vec![3.,3., 7.,3., 7.,7., 3.,7.]];
+  let grown = offset_polygon(&rings, 2, 1., JoinStyle::Miter(2.));
+  assert_eq!(grown.len(), 2);
+  assert!((rings_area(&grown) - 140.).abs()<1e-9);
+
+  // a self-intersecting ring is offset from what triangulate fills, the same as a polygon of that ring
+  let twist: Vec<f64> = vec![0.,0., 0.,3., 3.,0., 3.,4., -1.,0.];
+  let (new_data, indices) = triangulate(&mut twist.clone(), 2);
+  assert!((rings_area(&offset(&twist, 2, 0., JoinStyle::Bevel)) - mesh_area(&new_data, &indices)).abs()<1e-9);
+  for d in [-0.2, 0.5] {
+    let (a, b) = (offset(&twist, 2, d, JoinStyle::Bevel), offset_polygon(&[twist.clone()], 2, d, JoinStyle::Bevel));
+    assert!((rings_area(&a) - rings_area(&b)).abs()<1e-9);
+  }
+
+  let (new_data, indices) = triangulate_offset(&square, 2, -1., JoinStyle::Bevel);
+  assert!((mesh_area(&new_data, &indices) - 64.).abs()<1e-9);
+}
+
+#[test]
+fn test_boolean() {
+  use louvre::boolean::*;
+
+  let area = |(new_data, indices): (Vec<f64>, Vec<usize>)| mesh_area(&new_data, &indices);
+  let a: Vec<f64> = vec![0.,0., 4.,0., 4.,4., 0.,4.];
+  let b: Vec<f64> = vec![2.,2., 2.,6., 6.,6., 6.,2.];
+  assert_eq!(area(triangulate_boolean(&a, &b, 2, BooleanOp::Union)), 28.);
+  assert_eq!(area(triangulate_boolean(&a, &b, 2, BooleanOp::Intersection)), 4.);
+  assert_eq!(area(triangulate_boolean(&a, &b, 2, BooleanOp::Difference)), 12.);
+  assert_eq!(area(triangulate_boolean(&a, &b, 2, BooleanOp::Xor)), 24.);
+  assert_eq!(boolean(&a, &b, 2, BooleanOp::Intersection), vec![vec![4.,4., 2.,4., 2.,2., 4.,2.]]);
+  assert_eq!(boolean(&a, &b, 2, BooleanOp::Xor).len(), 2);
+
+  // a self-intersecting bow tie cut by a square
+  let bowtie: Vec<f64> = vec![0.,0., 4.,4., 4.,0., 0.,4.];
+  let (new_data, indices) = triangulate_boolean(&bowtie, &[0.,0., 4.,0., 4.,2., 0.,2.], 2, BooleanOp::Intersection);
+  assert_eq!(mesh_area(&new_data, &indices), 4.);
+
+  // a self-intersecting operand fills what triangulate fills
+  let twist: Vec<f64> = vec![0.,0., 0.,3., 3.,0., 3.,4., -1.,0.];
+  let far: Vec<f64> = vec![10.,10., 11.,10., 11.,11., 10.,11.];
+  assert_eq!(area(triangulate_boolean(&twist, &far, 2, BooleanOp::Union)), area(triangulate(&mut twist.clone(), 2))+1.);
+  assert_eq!(area(triangulate_boolean(&twist, &twist, 2, BooleanOp::Intersection)), area(triangulate(&mut twist.clone(), 2)));
+  assert!(boolean(&twist, &twist, 2, BooleanOp::Difference).is_empty());
+  // one of its cycles passes a point twice around a loop running the other way, which is filled too
+  let lobe: Vec<f64> = vec![8.6,9.2, 8.4,16.8, 13.1,9.1, 6.5,5.9, 0.1,19.2, 17.7,15.8, 18.2,3.2, 8.8,3.4, 11.7,1.2, 17.6,8.9, 4.,0.4];
+  let far: Vec<f64> = vec![100.,100., 101.,100., 101.,101., 100.,101.];
+  assert!((area(triangulate_boolean(&lobe, &far, 2, BooleanOp::Union)) - 192.0814136889534).abs()<1e-9);
+
+  // the mesh covers the rings where points found on segments line up with each other
+  for (a, b) in [
+    (vec![3.1,8.3, 6.9,7.5, 2.6,3.7, 7.3,7.9, 4.,5., 9.,8.7, 3.4,7.4], vec![9.8,7.3, 7.4,2.6, 2.2,9.4, 4.2,2.1, 6.6,3.2, 5.3,0.1, 1.8,1.7, 1.5,7.5, 5.9,1.8]),
+    (vec![6.4,0.7, 5.3,9.4, 0.9,1.3, 7.8,1.8, 1.7,7.2, 0.4,2.6, 3.6,4.1, 5.,3.8, 4.8,9.1, 5.,7.6], vec![7.2,0.4, 0.,8.5, 0.6,4.4, 8.5,9.9, 3.8,0.4]),
+  ] {
+    let rings = boolean(&a, &b, 2, BooleanOp::Union);
+    assert!((area(triangulate_boolean(&a, &b, 2, BooleanOp::Union)) - rings_area(&rings)).abs()<1e-9);
+  }
+
+  // a polygon with a hole
+  let frame = vec![vec![0.,0., 10.,0., 10.,10., 0.,10.], vec![2.,2., 8.,2., 8.,8., 2.,8.]];
+  let bar = vec![vec![-1.,4., 11.,4., 11.,6., -1.,6.]];
+  let rings = boolean_rings(&frame, &bar, 2, BooleanOp::Difference);
+  assert_eq!(rings.len(), 2);
+  let rings = boolean_rings(&frame, &bar, 2, BooleanOp::Union);
+  assert_eq!(rings.len(), 3);
+}
+
+#[test]
+fn test_resolve_self_intersections() {
+  use louvre::utils::*;
+
+  let rings = resolve_self_intersections(&mut vec![0.,0., 2.,2., 2.,0., 0.,2.], 2);
+  assert_eq!(rings.len(), 2);
+  assert!(rings.iter().all(|r| r.data.len()==6 && signed_area(&r.data, 2)==Winding::CCW));
+  let mut windings: Vec<Winding> = rings.iter().map(|r| r.winding).collect();
+  windings.sort_by_key(|w| *w==Winding::CW);
+  assert_eq!(windings, vec![Winding::CCW, Winding::CW]);
+
+  // the rings are what triangulate fills
+  let mut data: Vec<f64> = vec![0.,0., 0.,3., 3.,0., 3.,4., -1.,0.];
+  let (new_data, _) = triangulate(&mut data.clone(), 2);
+  let rings = resolve_self_intersections(&mut data, 2);
+  assert_eq!(rings.len(), 3);
+  assert_eq!(rings.iter().flat_map(|r| r.data.clone()).collect::<Vec<f64>>(), new_data);
+
+  // a simple polygon comes back in CCW
+  let rings = resolve_self_intersections(&mut vec![0.,0., 0.,1., 1.,1., 1.,0.], 2);
+  assert_eq!(rings.len(), 1);
+  assert_eq!(rings[0].winding, Winding::CCW);
+  assert_eq!(signed_area(&rings[0].data, 2), Winding::CCW);
+  assert_eq!(make_valid(&mut vec![0.,0., 0.,1., 1.,1., 1.,0.], 2), vec![rings[0].data.clone()]);
+}
+
+#[test]
+fn test_measure() {
+  use louvre::measure::*;
+  use louvre::utils::signed_area_value;
+
+  let square: Vec<f64> = vec![0.,0., 2.,0., 2.,2., 0.,2.];
+  assert_eq!(signed_area_value(&square, 2), 4.);
+  assert_eq!(signed_area_value(&[0.,0., 0.,2., 2.,2., 2.,0.], 2), -4.);
+  assert_eq!(measure(&square, 2), Measurements { area: 4., centroid: (1., 1.), perimeter: 8. });
+
+  // the shoelace sum of a bow tie is 0, but it covers two triangles
+  let bowtie: Vec<f64> = vec![0.,0., 2.,2., 2.,0., 0.,2., 0.,0.];
+  assert_eq!(signed_area_value(&bowtie, 2), 0.);
+  assert_eq!(filled_area(&bowtie, 2), 2.);
+  assert_eq!(centroid(&bowtie, 2), (1., 1.));
+  assert!((perimeter(&bowtie, 2) - (4. + 4.*2f64.sqrt())).abs()<1e-12);
+
+  // dim 3, and agreeing with the triangulation
+  let mut data: Vec<f64> = vec![0.,0.,5., 0.,3.,5., 3.,0.,5., 3.,4.,5., -1.,0.,5.];
+  let m = measure(&data, 3);
+  let (new_data, indices) = triangulate(&mut data, 3);
+  assert!((m.area - mesh_area(&new_data, &indices)).abs()<1e-12);
+}
+
+#[test]
+fn test_triangle_index() {
+  use louvre::locate::*;
+
+  let mut data: Vec<f64> = vec![0.,0., 10.,0., 10.,10., 5.,2., 0.,10.];
+  let (new_data, indices) = triangulate(&mut data, 2);
+  let index = TriangleIndex::new(&new_data, &indices);
+  assert_eq!(index.len(), 3);
+
+  let hit = index.locate(9., 1.).unwrap();
+  let t = &indices[hit.triangle*3..hit.triangle*3+3];
+  // barycentric coordinates give the point back
+  let x: f64 = (0..3).map(|j| new_data[t[j]*2]*hit.barycentric[j]).sum();
+  let y: f64 = (0..3).map(|j| new_data[t[j]*2+1]*hit.barycentric[j]).sum();
+  assert!((x-9.).abs()<1e-12 && (y-1.).abs()<1e-12);
+
+  // the notch and the outside
+  assert_eq!(index.locate(5., 5.), None);
+  assert_eq!(index.locate(-1., 5.), None);
+  assert!(index.locate(10., 10.).is_some());
+
+  let hits = index.locate_all(&[1.,1.,0., 5.,5.,0., 9.8,9.,0.], 3);
+  assert_eq!(hits.iter().map(|h| h.is_some()).collect::<Vec<bool>>(), vec![true, false, true]);
+
+  // interpolating the coordinates themselves
+  let v = index.interpolate(1., 7., &new_data, 2).unwrap();
+  assert!((v[0]-1.).abs()<1e-12 && (v[1]-7.).abs()<1e-12);
+
+  assert_eq!(TriangleIndex::new(&[], &[]).locate(0., 0.), None);
+
+  // a long thin mesh doesn't take a cell per unit of its length
+  let thin: Vec<f64> = vec![0.,0., 1e12,0., 1e12,1e-6, 0.,1e-6];
+  let index = TriangleIndex::new(&thin, &[0, 1, 2, 0, 2, 3]);
+  assert!(index.locate(5e11, 5e-7).is_some());
+}
+
+
+#[test]
+fn test_half_edge_mesh() {
+  use louvre::halfedge::*;
+
+  let mut data: Vec<f64> = vec![0.,0., 10.,0., 10.,10., 5.,2., 0.,10.];
+  let mesh = triangulate_half_edges(&mut data, 2);
+  assert_eq!((mesh.vertex_count(), mesh.triangle_count()), (5, 3));
+  // twins are mutual and reversed
+  for h in 0..9 {
+    if let Some(g) = mesh.twin(h) {
+      assert_eq!(mesh.twin(g), Some(h));
+      assert_eq!((mesh.origin(g), mesh.target(g)), (mesh.target(h), mesh.origin(h)));
+    }
+  }
+  assert_eq!(mesh.boundary_edges().count(), 5);
+  assert_eq!((0..3).map(|t| mesh.neighbours(t).count()).sum::<usize>(), 4);
+  let loops = mesh.boundary_loops();
+  assert_eq!(loops.len(), 1);
+  assert_eq!(loops[0].len(), 5);
+
+  // a square with a square hole
+  let rings = vec![
+    vec![0.,0., 10.,0., 10.,10., 0.,10.],
+    vec![3.,3., 3.,7., 7.,7., 7.,3.],
+  ];
+  let (new_data, indices) = triangulate_polygon(&rings, 2);
+  let mesh = HalfEdgeMesh::new(&new_data, &indices);
+  assert_eq!(mesh.vertex_count(), 8);
+  let mut loops = mesh.boundary_loops();
+  assert_eq!(loops.len(), 2);
+  // outer loop counter-clockwise, the hole clockwise
+  let windings: Vec<Winding> = loops.iter().map(|l| {
+    let points: Vec<f64> = l.iter().flat_map(|&v| [mesh.positions[v*2], mesh.positions[v*2+1]]).collect();
+    signed_area(&points, 2)
+  }).collect();
+  assert!(windings.contains(&Winding::CCW) && windings.contains(&Winding::CW));
+  loops.sort_by_key(|l| l.len());
+  assert_eq!((loops[0].len(), loops[1].len()), (4, 4));
+
+  // one-ring of the corner (0, 0): counter-clockwise, ending at (0, 10)
+  let v = (0..8).find(|&v| mesh.positions[v*2]==0. && mesh.positions[v*2+1]==0.).unwrap();
+  let ring = mesh.one_ring(v);
+  assert_eq!(ring.len(), mesh.outgoing(v).len()+1);
+  assert_eq!((mesh.positions[ring[0]*2], mesh.positions[ring[0]*2+1]), (10., 0.));
+  let last = *ring.last().unwrap();
+  assert_eq!((mesh.positions[last*2], mesh.positions[last*2+1]), (0., 10.));
+}
+
+
+#[test]
+fn test_convex_partition() {
+  use louvre::convex::*;
+  use louvre::utils::signed_area_value;
+
+  let is_convex = |p: &Vec<f64>| {
+    let n = p.len()/2;
+    (0..n).all(|i| {
+      let (a, b, c) = (i, (i+1)%n, (i+2)%n);
+      (p[b*2]-p[a*2])*(p[c*2+1]-p[b*2+1]) - (p[b*2+1]-p[a*2+1])*(p[c*2]-p[b*2]) > 0.
+    })
+  };
+
+  // a convex polygon comes back whole
+  let mut hexagon: Vec<f64> = vec![2.,0., 4.,1., 4.,3., 2.,4., 0.,3., 0.,1.];
+  let pieces = convex_partition(&mut hexagon, 2);
+  assert_eq!(pieces.len(), 1);
+  assert_eq!(pieces[0].len(), 12);
+
+  // one reflex vertex needs two pieces
+  let mut notch: Vec<f64> = vec![0.,0., 2.,0., 2.,1., 1.,1., 1.,2., 0.,2.];
+  let pieces = convex_partition(&mut notch, 2);
+  assert_eq!(pieces.len(), 2);
+  assert!(pieces.iter().all(is_convex));
+  let area: f64 = pieces.iter().map(|p| signed_area_value(p, 2)).sum();
+  assert!((area-3.).abs()<1e-9);
+
+  // a square with a hole
+  let rings = vec![
+    vec![0.,0., 10.,0., 10.,10., 0.,10.],
+    vec![3.,3., 3.,7., 7.,7., 7.,3.],
+  ];
+  let (new_data, indices) = triangulate_polygon(&rings, 2);
+  let pieces = convex_partition_mesh(&new_data, &indices);
+  assert!(pieces.len()<indices.len()/3);
+  assert!(pieces.iter().all(is_convex));
+  let area: f64 = pieces.iter().map(|p| signed_area_value(p, 2)).sum();
+  assert!((area-84.).abs()<1e-9);
+}
+
+
+#[test]
+fn test_monotone_backend() {
+  // area of the triangles, none of which may be CW
+  let area = |new_data: &Vec<f64>, indices: &Vec<usize>| -> f64 {
+    assert!(indices.chunks(3).all(|t| mesh_area(new_data, &t.to_vec())>=0.));
+    mesh_area(new_data, indices)
+  };
+
+  // a comb, with several split and merge vertices
+  let mut comb: Vec<f64> = vec![0.,0., 10.,0., 10.,10., 9.,10., 8.,2., 7.,10., 5.,10., 4.,3., 3.,10., 0.,10.];
+  let (new_data, indices) = triangulate_with(&mut comb.clone(), 2, Backend::Monotone);
+  assert_eq!(indices.len(), 8*3);
+  assert!((area(&new_data, &indices)-85.).abs()<1e-9);
+  let mut turned: Vec<f64> = comb.chunks(2).flat_map(|p| [p[1], p[0]]).collect();
+  let (new_data, indices) = triangulate_with(&mut turned, 2, Backend::Monotone);
+  assert_eq!(indices.len(), 8*3);
+  assert!((area(&new_data, &indices)-85.).abs()<1e-9);
+
+  // a long comb, with hundreds of edges in the sweep status at once
+  let mut long: Vec<f64> = vec![400.,10., 0.,10.];
+  for k in 0..200 {
+    let (x, l) = ((k*2) as f64, ((k*37)%11) as f64);
+    long.extend([x,0., x+1.,1.+l*0.5]);
+  }
+  let (new_data, indices) = triangulate_with(&mut long.clone(), 2, Backend::Monotone);
+  assert_eq!(indices.len(), (long.len()/2-2)*3);
+  assert!((area(&new_data, &indices)-louvre::utils::signed_area_value(&long, 2)).abs()<1e-9);
+
+  // self-intersecting: the same decomposition, and the same area
+  let mut data: Vec<f64> = vec![0.,0., 0.,3., 3.,0., 3.,4., -1.,0.];
+  let (new_data, indices) = triangulate_with(&mut data, 2, Backend::Monotone);
+  assert_eq!(new_data, triangulate(&mut data, 2).0);
+  assert!((area(&new_data, &indices)-5.5).abs()<1e-9);
+
+  // repeated points, at a hole bridge
+  let mut bridged: Vec<f64> = vec![0.,0., 4.,0., 4.,4., 0.,4., 0.,0., 1.,1., 1.,3., 3.,3., 3.,1., 1.,1.];
+  let (new_data, indices) = triangulate_with(&mut bridged, 2, Backend::Monotone);
+  assert!((area(&new_data, &indices)-12.).abs()<1e-9);
+
+  // a point on an edge: (3, 12) is on the edge from (6, 14) to (0, 10)
+  let mut touching: Vec<f64> = vec![3.,12., 17.,6., 10.,19., 6.,14., 0.,10., 8.,5., 10.,3., 14.,6.];
+  let expected = louvre::utils::signed_area_value(&touching, 2);
+  let (new_data, indices) = triangulate_with(&mut touching, 2, Backend::Monotone);
+  assert!((area(&new_data, &indices)-expected).abs()<1e-9);
+
+  // a straight angle: (2, 2) is on the line from (4, 1) to (1.2, 2.4), so the triangle across it has no area and is left out
+  let mut straight: Vec<f64> = vec![2.,2., 1.2,2.4, 0.,0., 4.,1.];
+  let expected = louvre::utils::signed_area_value(&straight, 2);
+  let (new_data, indices) = triangulate_with(&mut straight, 2, Backend::Monotone);
+  assert_eq!(indices.len(), 3);
+  assert!((area(&new_data, &indices)-expected).abs()<1e-9);
+}
+
+
+#[test]
+fn test_stripify() {
+  use louvre::strip::*;
+
+  // triangles of a strip buffer, CCW, skipping restarts and degenerate ones
+  let decode = |p: &Primitives, restart: Option<usize>| -> Vec<[usize; 3]> {
+    let mut triangles: Vec<[usize; 3]> = Vec::new();
+    for strip in p.strips.split(|&i| Some(i)==restart) {
+      for k in 2..strip.len() {
+        let (a, b, c) = (strip[k-2], strip[k-1], strip[k]);
+        if a==b || b==c || c==a {
+          continue;
+        }
+        let t = if k%2==0 { [a, b, c] } else { [b, a, c] };
+        let xy: Vec<f64> = t.iter().flat_map(|&i| [p.positions[i*2], p.positions[i*2+1]]).collect();
+        assert_eq!(signed_area(&xy, 2), Winding::CCW);
+        triangles.push(t);
+      }
+    }
+    triangles
+  };
+
+  let rings = vec![
+    vec![0.,0., 10.,0., 10.,10., 0.,10.],
+    vec![3.,3., 3.,7., 7.,7., 7.,3.],
+  ];
+  let (new_data, indices) = triangulate_polygon(&rings, 2);
+  for (join, restart) in [(StripJoin::Restart(usize::MAX), Some(usize::MAX)), (StripJoin::Degenerate, None)] {
+    let p = stripify(&new_data, &indices, join);
+    assert_eq!(p.list_len, 8*3);
+    let triangles = decode(&p, restart);
+    assert_eq!(triangles.len(), 8);
+    let area: f64 = triangles.iter().map(|t| {
+      let xy: Vec<f64> = t.iter().flat_map(|&i| [p.positions[i*2], p.positions[i*2+1]]).collect();
+      louvre::utils::signed_area_value(&xy, 2)
+    }).sum();
+    assert!((area-84.).abs()<1e-9);
+    if join!=StripJoin::Degenerate {
+      assert!(p.saved()>0);
+      assert_eq!(p.strips.len(), 8 + 2*p.strip_count + p.strip_count-1);
+    }
+  }
+
+  // a convex ring becomes a fan
+  let mut hexagon: Vec<f64> = vec![2.,0., 4.,1., 4.,3., 2.,4., 0.,3., 0.,1.];
+  let p = triangulate_strips(&mut hexagon, 2, StripJoin::Degenerate);
+  assert!(p.strips.is_empty());
+  assert_eq!(p.fans, vec![vec![0, 1, 2, 3, 4, 5]]);
+  assert_eq!((p.list_len, p.saved()), (12, 6));
+}
+
+
+#[test]
+fn test_vertex_cache() {
+  use louvre::cache::*;
+
+  assert_eq!(acmr(&[0, 1, 2], 16), 3.);
+  assert_eq!(acmr(&[0, 1, 2, 2, 1, 3], 16), 2.);
+  assert_eq!(acmr(&[0, 1, 2, 0, 2, 3], 2), 2.5);
+
+  // a comb: ear clipping walks back and forth along it
+  let mut data: Vec<f64> = Vec::new();
+  for k in 0..50 {
+    data.extend([k as f64*2., 0., k as f64*2.+1., 10.]);
+  }
+  data.extend([100., 0., 100., -1., 0., -1.]);
+  let (new_data, indices) = triangulate(&mut data, 2);
+  let (data, optimized) = optimize_vertex_cache(&new_data, 2, &indices, 16);
+  assert_eq!(data.len(), new_data.len());
+  assert_eq!(optimized.len(), indices.len());
+  assert!(acmr(&optimized, 16)<=acmr(&indices, 16));
+
+  // the same triangles, with the same winding
+  let triangles = |d: &Vec<f64>, i: &Vec<usize>| {
+    let mut t: Vec<Vec<u64>> = i.chunks(3).map(|t| {
+      let k = (0..3).min_by(|&a, &b| d[t[a]*2].partial_cmp(&d[t[b]*2]).unwrap().then(d[t[a]*2+1].partial_cmp(&d[t[b]*2+1]).unwrap())).unwrap();
+      (0..3).flat_map(|j| { let v = t[(k+j)%3]; [d[v*2].to_bits(), d[v*2+1].to_bits()] }).collect()
+    }).collect();
+    t.sort();
+    t
+  };
+  assert_eq!(triangles(&data, &optimized), triangles(&new_data, &indices));
+  // vertices in the order of first use
+  let mut seen = 0;
+  for &v in optimized.iter() {
+    assert!(v<=seen);
+    if v==seen {
+      seen += 1;
+    }
+  }
+}
+
+
+#[test]
+fn test_uvs() {
+  use louvre::uv::*;
+
+  let data: Vec<f64> = vec![2.,1., 6.,1., 6.,3., 2.,3.];
+  assert_eq!(uvs(&data, 2, UvMode::BoundingBox), vec![0.,0., 1.,0., 1.,1., 0.,1.]);
+  assert_eq!(uvs(&data, 2, UvMode::World(2., 4.)), vec![1.,0.25, 3.,0.25, 3.,0.75, 1.,0.75]);
+  // swap x and y, then shift u
+  assert_eq!(uvs(&data, 2, UvMode::Affine([0.,1.,1., 1.,0.,0.])), vec![2.,2., 2.,6., 4.,6., 4.,2.]);
+  // dim 3: z is kept in the interleaved buffer
+  let data3: Vec<f64> = vec![0.,0.,7., 2.,0.,7., 0.,1.,7.];
+  let uv = uvs(&data3, 3, UvMode::BoundingBox);
+  assert_eq!(interleave_uvs(&data3, 3, &uv), vec![0.,0.,7.,0.,0., 2.,0.,7.,1.,0., 0.,1.,7.,0.,1.]);
+
+  let mut square = data.clone();
+  let (buffer, indices) = triangulate_uv(&mut square, 2, UvMode::BoundingBox);
+  let (new_data, _) = triangulate(&mut data.clone(), 2);
+  assert_eq!(buffer.len(), new_data.len()*2);
+  assert_eq!(indices.len(), 6);
+  for v in buffer.chunks(4) {
+    assert_eq!([v[2], v[3]], [(v[0]-2.)/4., (v[1]-1.)/2.]);
+  }
+}
+
+
+#[test]
+fn test_triangulate_windings() {
+  // pentagram: the center pentagon is wound around twice, the points once
+  let star: Vec<f64> = (0..5).flat_map(|i| {
+    let a = std::f64::consts::PI*0.5 + (i*2) as f64 * std::f64::consts::PI*2./5.;
+    [a.cos(), a.sin()]
+  }).collect();
+  let (new_data, indices, windings) = triangulate_windings(&mut star.clone(), 2);
+  assert_eq!(windings.len(), indices.len()/3);
+  let area_of = |new_data: &Vec<f64>, indices: &Vec<usize>, windings: &Vec<i32>, w: i32| {
+    let picked: Vec<usize> = indices.chunks(3).zip(windings.iter()).filter(|&(_, &v)| v==w).flat_map(|(t, _)| t.to_vec()).collect();
+    mesh_area(new_data, &picked)
+  };
+  let (d1, i1) = triangulate(&mut star.clone(), 2);
+  assert!((mesh_area(&new_data, &indices) - mesh_area(&d1, &i1)).abs() < 1e-9);
+  assert!(windings.iter().all(|&w| w==1 || w==2));
+  // the inner corners are 0.38 away from the center, the points 1
+  let center: Vec<i32> = indices.chunks(3).zip(windings.iter())
+    .filter(|&(t, _)| t.iter().all(|&i| new_data[i*2].hypot(new_data[i*2+1])<0.5))
+    .map(|(_, &w)| w).collect();
+  assert!(!center.is_empty() && center.iter().all(|&w| w==2));
+  // what the even-odd rule leaves out is wound around twice
+  let (d2, i2) = louvre::fill::triangulate_fill(&[star], 2, FillRule::EvenOdd);
+  assert!((area_of(&new_data, &indices, &windings, 2) - (mesh_area(&d1, &i1) - mesh_area(&d2, &i2))).abs() < 1e-9);
+
+  // a figure 8 with a twist: its inner loop runs against the polygon
+  let data: Vec<f64> = vec![0.,0., 0.,3., 3.,0., 3.,4., -1.,0.];
+  let rings = resolve_self_intersections(&mut data.clone(), 2);
+  let ring_area_of = |winding: Winding| rings.iter().filter(|r| r.winding==winding).map(|r| louvre::utils::signed_area_value(&r.data, 2)).sum::<f64>();
+  for backend in [Backend::EarCut, Backend::Monotone] {
+    let (new_data, indices, windings) = triangulate_windings_with(&mut data.clone(), 2, backend);
+    assert_eq!(windings.iter().filter(|&&w| w==-1).count(), 1);
+    assert!((area_of(&new_data, &indices, &windings, -1) - ring_area_of(Winding::CW)).abs() < 1e-9);
+    assert!((area_of(&new_data, &indices, &windings, 1) - ring_area_of(Winding::CCW)).abs() < 1e-9);
+  }
+
+  // the triangles of a simple polygon are the ones of triangulate, all wound around once
+  let mut hilbert = asset_ring(include_str!("../../assets/hilbert.json"));
+  for backend in [Backend::EarCut, Backend::Monotone] {
+    let (new_data, indices, windings) = triangulate_windings_with(&mut hilbert, 2, backend);
+    assert_eq!((new_data, indices), triangulate_with(&mut hilbert, 2, backend));
+    assert!(windings.iter().all(|&w| w==1));
+  }
+}
+
+
+#[test]
+fn test_self_intersections() {
+  assert!(self_intersections(&mut vec![0.,0., 1.,0., 1.,1., 0.,1.], 2).is_empty());
+
+  let report = self_intersections(&mut vec![0.,0., 2.,2., 2.,0., 0.,2.], 2);
+  assert_eq!(report, vec![
+    SelfIntersection { segment_a: 0, segment_b: 2, point: (1., 1.), t: 0.5, u: 0.5, crossing: true },
+  ]);
+
+  // the vertex (2, 0) touches the bottom edge and turns back
+  let report = self_intersections(&mut vec![0.,0., 4.,0., 4.,4., 2.,0., 0.,4.], 2);
+  assert_eq!(report, vec![
+    SelfIntersection { segment_a: 0, segment_b: 3, point: (2., 0.), t: 0.5, u: 0., crossing: false },
+  ]);
+  // the same, clockwise and closed
+  let mut data: Vec<f64> = vec![0.,4., 2.,0., 4.,4., 4.,0., 0.,0., 0.,4.];
+  let report = self_intersections(&mut data, 2);
+  assert_eq!(data.len(), 10);
+  assert_eq!(report, vec![
+    SelfIntersection { segment_a: 0, segment_b: 3, point: (2., 0.), t: 1., u: 0.5, crossing: false },
+  ]);
+  // the points of the crossings are the ones triangulate inserts
+  let mut data: Vec<f64> = vec![0.,0., 0.,3., 3.,0., 3.,4., -1.,0.];
+  let points: Vec<f64> = self_intersections(&mut data.clone(), 2).iter().filter(|s| s.crossing).flat_map(|s| [s.point.0, s.point.1]).collect();
+  let expected = intersection_points(&mut data, 2);
+  let sort = |v: &[f64]| {
+    let mut p: Vec<(u64, u64)> = v.chunks(2).map(|p| (p[0].to_bits(), p[1].to_bits())).collect();
+    p.sort();
+    p
+  };
+  assert_eq!(sort(&points), sort(&expected));
+}
+
+
+#[test]
+fn test_editable_polygon() {
+  use louvre::edit::*;
+  use std::collections::BTreeMap;
+
+  let tri_area = |p: &[f64; 6]| ((p[2]-p[0])*(p[5]-p[1]) - (p[3]-p[1])*(p[4]-p[0]))/2.;
+  // the triangles kept by applying each diff agree with the polygon, and fill what triangulate fills
+  let check = |polygon: &EditablePolygon, kept: &mut BTreeMap<usize, [f64; 6]>, diff: Diff| {
+    for id in diff.removed.iter() {
+      assert!(kept.remove(id).is_some());
+    }
+    for t in diff.added.iter() {
+      assert!(tri_area(&t.points)>=0.);
+      assert!(kept.insert(t.id, t.points).is_none());
+    }
+    let current: BTreeMap<usize, [f64; 6]> = polygon.triangles().map(|t| (t.id, t.points)).collect();
+    assert_eq!(&current, kept);
+    let (d, i) = if polygon.len()<3 { (Vec::new(), Vec::new()) } else { triangulate(&mut polygon.data().to_vec(), 2) };
+    let (new_data, indices) = polygon.mesh();
+    assert!((mesh_area(&new_data, &indices) - mesh_area(&d, &i)).abs()<1e-9);
+  };
+
+  // a bowtie whose right lobe is a quad
+  let mut polygon = EditablePolygon::new(&[0.,0., 2.,2., 3.,1., 2.,0., 0.,2.], 2);
+  assert_eq!(polygon.len(), 5);
+  assert_eq!(polygon.cycle_count(), 2);
+  assert_eq!(polygon.intersections().len(), 1);
+  let mut kept: BTreeMap<usize, [f64; 6]> = polygon.triangles().map(|t| (t.id, t.points)).collect();
+  assert_eq!(kept.len(), 3);
+  let left: Vec<usize> = polygon.triangles().filter(|t| t.points.chunks(2).all(|p| p[0]<=1.)).map(|t| t.id).collect();
+  assert_eq!(left.len(), 1);
+
+  // moving a point of the right lobe keeps the left one
+  let diff = polygon.move_vertex(2, 4., 1.);
+  assert_eq!(diff.removed.len(), 2);
+  assert!(!diff.removed.contains(&left[0]));
+  assert_eq!(diff.added.len(), 2);
+  check(&polygon, &mut kept, diff);
+  assert!(polygon.move_vertex(2, 4., 1.).is_empty());
+
+  // so does inserting one, and removing it
+  let diff = polygon.insert(3, 4., 0.);
+  assert!(!diff.removed.contains(&left[0]));
+  assert_eq!(diff.added.len(), 3);
+  check(&polygon, &mut kept, diff);
+  let diff = polygon.remove(3);
+  assert!(!diff.removed.contains(&left[0]));
+  check(&polygon, &mut kept, diff);
+
+  // pulling the crossing apart makes one cycle
+  let diff = polygon.move_vertex(4, 1., -1.);
+  assert!(diff.removed.contains(&left[0]));
+  check(&polygon, &mut kept, diff);
+  assert_eq!(polygon.cycle_count(), 1);
+  assert!(polygon.intersections().is_empty());
+
+  // down to two points, there is nothing left
+  while polygon.len()>2 {
+    let diff = polygon.remove(0);
+    check(&polygon, &mut kept, diff);
+  }
+  assert!(kept.is_empty());
+
+  // drawing point by point, as the demo does
+  let mut polygon = EditablePolygon::with_backend(&[], 2, Backend::Monotone);
+  let mut kept: BTreeMap<usize, [f64; 6]> = BTreeMap::new();
+  let mut seed: u64 = 7;
+  let mut random = || {
+    seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    (seed>>11) as f64 / (1u64<<53) as f64 * 100.
+  };
+  for _ in 0..20 {
+    let (x, y) = (random(), random());
+    let diff = polygon.push(x, y);
+    check(&polygon, &mut kept, diff);
+  }
+  for k in 0..20 {
+    let (x, y) = (random(), random());
+    let diff = polygon.move_vertex(k*7%polygon.len(), x, y);
+    check(&polygon, &mut kept, diff);
+  }
+
+  // an edit of a large simple outline which leaves it simple only changes the triangles around the point
+  let star: Vec<f64> = (0..2000).flat_map(|k| {
+    let (angle, radius) = (k as f64*std::f64::consts::TAU/2000., if k%2==0 { 100. } else { 90. });
+    [radius*angle.cos(), radius*angle.sin()]
+  }).collect();
+  let mut polygon = EditablePolygon::new(&star, 2);
+  let mut kept: BTreeMap<usize, [f64; 6]> = polygon.triangles().map(|t| (t.id, t.points)).collect();
+  assert_eq!(kept.len(), 1998);
+  let (x, y) = polygon.point(500);
+  let diff = polygon.move_vertex(500, x*0.97, y*0.97);
+  assert!(diff.removed.len()<10 && diff.added.len()<10);
+  check(&polygon, &mut kept, diff);
+  let (x, y) = polygon.point(700);
+  let diff = polygon.insert(700, x*1.01, y*1.01);
+  assert!(diff.removed.len()<10 && diff.added.len()<10);
+  check(&polygon, &mut kept, diff);
+  let diff = polygon.remove(900);
+  assert!(diff.removed.len()<10 && diff.added.len()<10);
+  check(&polygon, &mut kept, diff);
+  assert_eq!(kept.len(), 1998);
+
+  // and so does a run of them, which reuse the places of the points and triangles removed before
+  for k in 0..30 {
+    let i = 1000 + k%3;
+    let diff = match k%3 {
+      0 => polygon.remove(i),
+      1 => {
+        let ((x0, y0), (x1, y1)) = (polygon.point(i-1), polygon.point(i));
+        polygon.insert(i, (x0+x1)*0.505, (y0+y1)*0.505)
+      },
+      _ => {
+        let (x, y) = polygon.point(i);
+        polygon.move_vertex(i, x*0.99, y*0.99)
+      },
+    };
+    assert!(diff.removed.len()<10 && diff.added.len()<10);
+    check(&polygon, &mut kept, diff);
+  }
+}
+
+
+#[test]
+fn test_triangulate_canonical() {
+  let points: Vec<[f64; 2]> = vec![[0., 0.], [0., 3.], [3., 0.], [3., 4.], [-1., 0.]];
+  let expected = triangulate_canonical(&points.concat(), 2);
+
+  // the same polygon, from every point and both ways, closed, with -0 and with dim 3
+  for s in 0..points.len() {
+    let mut rotated: Vec<[f64; 2]> = points.clone();
+    rotated.rotate_left(s);
+    assert_eq!(triangulate_canonical(&rotated.concat(), 2), expected);
+    rotated.reverse();
+    assert_eq!(triangulate_canonical(&rotated.concat(), 2), expected);
+    rotated.push(rotated[0]);
+    assert_eq!(triangulate_canonical(&rotated.concat(), 2), expected);
+  }
+  let negative_zero: Vec<f64> = vec![-0., 0., 0., 3., 3., -0., 3., 4., -1., 0.];
+  assert_eq!(triangulate_canonical(&negative_zero, 2), expected);
+  let dim3: Vec<f64> = points.iter().flat_map(|p| [p[0], p[1], 9.]).collect();
+  assert_eq!(triangulate_canonical(&dim3, 3), expected);
+
+  let (new_data, indices) = expected;
+  assert_eq!(new_data, vec![
+    -1., 0.,  0., 0.,  0., 1.,
+    3., 0.,  3., 4.,  1., 2.,
+    0., 1.,  1., 2.,  0., 3.,
+  ]);
+  assert_eq!(indices, vec![0, 1, 2, 3, 4, 5, 6, 7, 8]);
+
+  // a figure 8 has no winding to go by
+  let bowtie: Vec<f64> = vec![0.,0., 2.,2., 2.,0., 0.,2.];
+  let reversed: Vec<f64> = bowtie.chunks(2).rev().flatten().copied().collect();
+  assert_eq!(triangulate_canonical(&bowtie, 2), triangulate_canonical(&reversed, 2));
+
+  // a larger one: the triangles start from their smallest index, are sorted, CCW, and fill what triangulate fills
+  let star: Vec<f64> = (0..40).flat_map(|k| {
+    let a = k as f64 * std::f64::consts::PI * 0.45;
+    let r = if k%2==1 { 3. } else { 10. };
+    [r*a.cos(), r*a.sin()]
+  }).collect();
+  let (new_data, indices) = triangulate_canonical(&star, 2);
+  let triangles: Vec<&[usize]> = indices.chunks(3).collect();
+  assert!(triangles.iter().all(|t| t[0]<t[1] && t[0]<t[2]));
+  assert!(triangles.windows(2).all(|w| w[0]<w[1]));
+  let tri_area = |d: &[f64], t: &[usize]| {
+    let p = |v: usize| (d[v*2], d[v*2+1]);
+    let ((ax, ay), (bx, by), (cx, cy)) = (p(t[0]), p(t[1]), p(t[2]));
+    ((bx-ax)*(cy-ay) - (by-ay)*(cx-ax))/2.
+  };
+  assert!(triangles.iter().all(|t| tri_area(&new_data, t)>=0.));
+  let (d, i) = triangulate(&mut star.clone(), 2);
+  let expected: f64 = i.chunks(3).map(|t| tri_area(&d, t)).sum();
+  let area: f64 = triangles.iter().map(|t| tri_area(&new_data, t)).sum();
+  assert!((area-expected).abs()<1e-9);
+  let mut shifted = star.clone();
+  shifted.rotate_left(14);
+  assert_eq!(triangulate_canonical(&shifted, 2), (new_data, indices));
+}